@@ -0,0 +1,477 @@
+//! Music and sound effects.
+//!
+//! Every sound plays on a channel (music or sound effects) whose volume, set in the options,
+//! is multiplied by the master volume. The background music of a level is the track named by
+//! its `music` field in LDtk (a file of `assets/audio/music`), crossfaded when the player
+//! changes level.
+//!
+//! The sounds are optional: no file is shipped yet, and only the files found in
+//! `assets/audio` when the game starts are loaded, so a missing sound is silent instead of
+//! an asset error.
+
+use std::path::{Path, PathBuf};
+
+use bevy::asset::io::AssetSourceId;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy::tasks::futures_lite::{future, StreamExt};
+use bevy::tasks::{block_on, IoTaskPool, Task};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::combat::attack::AttackHitEvent;
+use crate::config::AudioConfig;
+use crate::engine::damage::{DamageApplied, DeathEvent};
+use crate::entities::player::{JumpEvent, Player};
+use crate::InGameplay;
+
+/// Duration of the crossfade between two music tracks (in seconds)
+pub const MUSIC_CROSSFADE_SECONDS: f32 = 1.5;
+
+const SFX_DIRECTORY: &str = "audio/sfx";
+const MUSIC_DIRECTORY: &str = "audio/music";
+
+/// Volume settings of the options menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioChannel {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl AudioChannel {
+    pub const ALL: [AudioChannel; 3] =
+        [AudioChannel::Master, AudioChannel::Music, AudioChannel::Sfx];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AudioChannel::Master => "Volume principal",
+            AudioChannel::Music => "Musique",
+            AudioChannel::Sfx => "Effets sonores",
+        }
+    }
+}
+
+/// Sound effects of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Jump,
+    /// An attack of the player touches a mob
+    Hit,
+    /// The player takes damage
+    Hurt,
+    Death,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 4] = [Sfx::Jump, Sfx::Hit, Sfx::Hurt, Sfx::Death];
+
+    fn path(&self) -> PathBuf {
+        let name = match self {
+            Sfx::Jump => "jump.ogg",
+            Sfx::Hit => "hit.ogg",
+            Sfx::Hurt => "hurt.ogg",
+            Sfx::Death => "death.ogg",
+        };
+        Path::new(SFX_DIRECTORY).join(name)
+    }
+}
+
+fn music_path(name: &str) -> PathBuf {
+    Path::new(MUSIC_DIRECTORY).join(format!("{}.ogg", name))
+}
+
+/// Sound files present in the assets, listed once when the game starts
+#[derive(Resource, Default)]
+pub struct AudioFiles {
+    listing: Option<Task<HashSet<PathBuf>>>,
+    /// `None` until the listing is done
+    found: Option<HashSet<PathBuf>>,
+}
+
+impl AudioFiles {
+    fn contains(&self, path: &Path) -> bool {
+        self.found
+            .as_ref()
+            .is_some_and(|found| found.contains(path))
+    }
+}
+
+/// Event asking for a sound effect to be played
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaySfx(pub Sfx);
+
+/// Sound effects found in the assets, loaded once for the whole game
+#[derive(Resource, Default)]
+pub struct SfxHandles(HashMap<Sfx, Handle<AudioSource>>);
+
+/// Système qui liste les fichiers des dossiers de sons, sans erreur si un dossier manque
+fn list_audio_files(asset_server: Res<AssetServer>, mut audio_files: ResMut<AudioFiles>) {
+    let asset_server = asset_server.clone();
+    audio_files.listing = Some(IoTaskPool::get().spawn(async move {
+        let mut found = HashSet::new();
+        let Ok(source) = asset_server.get_source(AssetSourceId::Default) else {
+            return found;
+        };
+        for directory in [SFX_DIRECTORY, MUSIC_DIRECTORY] {
+            if let Ok(mut paths) = source.reader().read_directory(Path::new(directory)).await {
+                while let Some(path) = paths.next().await {
+                    found.insert(path);
+                }
+            }
+        }
+        found
+    }));
+}
+
+/// Système qui charge les effets sonores présents une fois les fichiers listés
+fn load_found_sfx(
+    asset_server: Res<AssetServer>,
+    mut audio_files: ResMut<AudioFiles>,
+    mut handles: ResMut<SfxHandles>,
+) {
+    let Some(listing) = audio_files.listing.as_mut() else {
+        return;
+    };
+    let Some(found) = block_on(future::poll_once(listing)) else {
+        return;
+    };
+    audio_files.listing = None;
+    audio_files.found = Some(found);
+
+    for sfx in Sfx::ALL {
+        let path = sfx.path();
+        if audio_files.contains(&path) {
+            handles.0.insert(sfx, asset_server.load(path));
+        }
+    }
+}
+
+/// Background music track, playing or fading out
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct MusicTrack {
+    pub name: String,
+    /// Crossfade progress, from 0 (silent) to 1 (full music volume)
+    pub fade: f32,
+    pub fading_out: bool,
+}
+
+/// Set when the music of the selected level has to be looked up again
+#[derive(Resource, Default)]
+pub struct PendingLevelMusic(pub bool);
+
+/// Fade out every track but `wanted`, returns whether `wanted` still has to be started
+fn crossfade_to<'a>(
+    tracks: impl Iterator<Item = &'a mut MusicTrack>,
+    wanted: Option<&str>,
+) -> bool {
+    let mut playing = false;
+    for track in tracks {
+        let is_wanted = !playing && wanted == Some(track.name.as_str());
+        // Coming back to a level resumes its track if it is still fading out
+        track.fading_out = !is_wanted;
+        playing |= is_wanted;
+    }
+    wanted.is_some() && !playing
+}
+
+/// Système qui lance la musique du niveau sélectionné (champ `music` du niveau dans LDtk)
+#[allow(clippy::too_many_arguments)]
+fn play_level_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_selection: Res<LevelSelection>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+    audio_files: Res<AudioFiles>,
+    mut pending: ResMut<PendingLevelMusic>,
+    mut tracks: Query<&mut MusicTrack>,
+) {
+    if !level_selection.is_changed() && !pending.0 {
+        return;
+    }
+
+    // The project is loaded a few frames after the level is selected
+    let ldtk_project = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle));
+    let (Some(ldtk_project), Some(_)) = (ldtk_project, &audio_files.found) else {
+        pending.0 = true;
+        return;
+    };
+    pending.0 = false;
+
+    let Some(level) = ldtk_project.find_raw_level_by_level_selection(&level_selection) else {
+        return;
+    };
+    // Levels without the field, or naming a track that is not shipped, keep silent
+    let wanted = level
+        .get_maybe_string_field("music")
+        .ok()
+        .and_then(Option::as_ref)
+        .filter(|name| audio_files.contains(&music_path(name)));
+
+    let start = crossfade_to(
+        tracks.iter_mut().map(Mut::into_inner),
+        wanted.map(String::as_str),
+    );
+    if let (true, Some(name)) = (start, wanted) {
+        commands.spawn((
+            AudioPlayer::new(asset_server.load(music_path(name))),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            MusicTrack {
+                name: name.clone(),
+                fade: 0.0,
+                fading_out: false,
+            },
+        ));
+    }
+}
+
+fn request_level_music(mut pending: ResMut<PendingLevelMusic>) {
+    pending.0 = true;
+}
+
+/// The music fades out when leaving the game
+fn stop_music(mut tracks: Query<&mut MusicTrack>) {
+    crossfade_to(tracks.iter_mut().map(Mut::into_inner), None);
+}
+
+/// Système qui fait progresser les fondus et applique le volume de la musique
+fn fade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    audio_config: Res<AudioConfig>,
+    global_volume: Res<GlobalVolume>,
+    mut tracks: Query<(Entity, &mut MusicTrack, Option<&AudioSink>)>,
+) {
+    let step = time.delta_secs() / MUSIC_CROSSFADE_SECONDS;
+
+    for (entity, mut track, sink) in &mut tracks {
+        if track.fading_out {
+            track.fade = (track.fade - step).max(0.0);
+            if track.fade == 0.0 {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+        } else {
+            track.fade = (track.fade + step).min(1.0);
+        }
+
+        // The global volume is only applied by Bevy when the sink is created
+        if let Some(sink) = sink {
+            sink.set_volume(global_volume.volume.get() * audio_config.music_volume * track.fade);
+        }
+    }
+}
+
+/// Système qui associe un effet sonore aux événements du jeu
+fn sfx_from_game_events(
+    mut jump_events: EventReader<JumpEvent>,
+    mut hit_events: EventReader<AttackHitEvent>,
+    mut damage_events: EventReader<DamageApplied>,
+    mut death_events: EventReader<DeathEvent>,
+    players: Query<(), With<Player>>,
+    mut sfx_events: EventWriter<PlaySfx>,
+) {
+    let jumped = jump_events
+        .read()
+        .any(|event| players.contains(event.entity));
+    if jumped {
+        sfx_events.send(PlaySfx(Sfx::Jump));
+    }
+    for hit in hit_events.read() {
+        debug!(
+            "{:?} touche {:?} ({} dégâts)",
+            hit.attacker, hit.target, hit.damage
+        );
+        sfx_events.send(PlaySfx(Sfx::Hit));
+    }
+    // Hits absorbed by the invincibility frames are not applied, so they are silent
+    let hurt = damage_events
+        .read()
+        .filter(|event| players.contains(event.entity))
+        .count();
+    if hurt > 0 {
+        sfx_events.send(PlaySfx(Sfx::Hurt));
+    }
+    for _ in death_events.read() {
+        sfx_events.send(PlaySfx(Sfx::Death));
+    }
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    handles: Res<SfxHandles>,
+    audio_config: Res<AudioConfig>,
+    mut sfx_events: EventReader<PlaySfx>,
+) {
+    for PlaySfx(sfx) in sfx_events.read() {
+        // Only the sound effects found in the assets have a handle
+        let Some(handle) = handles.0.get(sfx) else {
+            continue;
+        };
+        commands.spawn((
+            AudioPlayer::new(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(audio_config.sfx_volume)),
+        ));
+    }
+}
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySfx>()
+            .init_resource::<AudioFiles>()
+            .init_resource::<SfxHandles>()
+            .init_resource::<PendingLevelMusic>()
+            .add_systems(Startup, list_audio_files)
+            .add_systems(OnEnter(InGameplay), request_level_music)
+            .add_systems(OnExit(InGameplay), stop_music)
+            .add_systems(
+                Update,
+                (
+                    (play_level_music, sfx_from_game_events).run_if(in_state(InGameplay)),
+                    fade_music,
+                    (load_found_sfx, play_sfx).chain(),
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::TestGame;
+
+    /// App playing the sound effects of an asset folder, once its audio files are listed
+    fn sfx_app(asset_folder: &Path) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: asset_folder.to_string_lossy().into_owned(),
+                ..default()
+            },
+        ))
+        .init_asset::<AudioSource>()
+        .add_event::<PlaySfx>()
+        .init_resource::<AudioConfig>()
+        .init_resource::<AudioFiles>()
+        .init_resource::<SfxHandles>()
+        .add_systems(Startup, list_audio_files)
+        .add_systems(Update, (load_found_sfx, play_sfx).chain());
+
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<AudioFiles>().found.is_some() {
+                return app;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the audio files of {:?} should be listed", asset_folder);
+    }
+
+    #[test]
+    fn missing_sounds_are_never_loaded_nor_played() {
+        let dir = std::env::temp_dir().join(format!("theseventh-no-audio-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut app = sfx_app(&dir);
+
+        assert!(app.world().resource::<SfxHandles>().0.is_empty());
+        app.world_mut().send_event(PlaySfx(Sfx::Jump));
+        app.update();
+        let players = app
+            .world_mut()
+            .query::<&AudioPlayer>()
+            .iter(app.world())
+            .count();
+        assert_eq!(players, 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn only_the_sounds_found_in_the_assets_are_loaded() {
+        let dir = std::env::temp_dir().join(format!("theseventh-audio-{}", std::process::id()));
+        fs::create_dir_all(dir.join(SFX_DIRECTORY)).unwrap();
+        fs::write(dir.join(Sfx::Jump.path()), []).unwrap();
+        let app = sfx_app(&dir);
+
+        let handles = &app.world().resource::<SfxHandles>().0;
+        assert_eq!(handles.keys().collect::<Vec<_>>(), [&Sfx::Jump]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn track(name: &str, fading_out: bool) -> MusicTrack {
+        MusicTrack {
+            name: name.to_string(),
+            fade: 0.5,
+            fading_out,
+        }
+    }
+
+    #[test]
+    fn changing_level_crossfades_to_the_new_track() {
+        let mut tracks = [track("forest", false)];
+
+        assert!(crossfade_to(tracks.iter_mut(), Some("cave")));
+        assert!(tracks[0].fading_out);
+
+        assert!(!crossfade_to(tracks.iter_mut(), None));
+        assert!(tracks[0].fading_out);
+    }
+
+    #[test]
+    fn coming_back_resumes_the_fading_track() {
+        let mut tracks = [track("cave", false), track("forest", true)];
+
+        assert!(!crossfade_to(tracks.iter_mut(), Some("forest")));
+        assert_eq!(tracks, [track("cave", true), track("forest", false)]);
+
+        // Same track in the next level: nothing to do
+        assert!(!crossfade_to(tracks.iter_mut(), Some("forest")));
+        assert!(!tracks[1].fading_out);
+    }
+
+    #[test]
+    fn changing_level_starts_the_music_named_in_ldtk() {
+        let mut game = TestGame::new();
+        game.settle();
+        // No track found in the assets yet
+        game.app
+            .init_asset::<AudioSource>()
+            .init_resource::<AudioConfig>()
+            .insert_resource(GlobalVolume::default())
+            .insert_resource(AudioFiles {
+                listing: None,
+                found: Some(HashSet::new()),
+            });
+        GameAudioPlugin.build(&mut game.app);
+        game.step(2);
+        assert!(game.find::<MusicTrack>().is_none());
+
+        // The music is only looked up again when the level changes
+        game.app.world_mut().resource_mut::<AudioFiles>().found =
+            Some(HashSet::from([music_path("fixture")]));
+        game.step(2);
+        assert!(game.find::<MusicTrack>().is_none());
+
+        *game.app.world_mut().resource_mut::<LevelSelection>() =
+            LevelSelection::iid("c0ffee00-0000-11f1-a000-000000000010");
+        game.step(2);
+        let track = game
+            .find::<MusicTrack>()
+            .expect("the level music should start");
+        let track = game.get::<MusicTrack>(track).unwrap();
+        assert_eq!(track.name, "fixture");
+        assert!(!track.fading_out);
+        assert!(
+            track.fade > 0.0 && track.fade < 1.0,
+            "the track should fade in"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::config::KeyBindings;
+use crate::core::item_effects::PlayerModifiers;
 use crate::engine::damage::DamageEvent;
+use crate::entities::movement::Movement;
 use crate::entities::player::{Player, Side};
-use crate::input::{is_button_just_pressed, GamepadState};
+use crate::entities::stats::Stats;
+use crate::input::actions::{Action, ActionState};
+use crate::{GameState, InGameplay};
 
 /// Marqueur pour la hitbox d'attaque
 #[derive(Component)]
@@ -59,22 +62,22 @@ pub fn setup_player_attack(
 }
 
 pub fn handle_attack_input(
-    input: Res<ButtonInput<KeyCode>>,
-    key_bindings: Res<KeyBindings>,
-    gamepad_state: Res<GamepadState>,
-    gamepads: Query<&Gamepad>,
-    mut query: Query<(Entity, &Transform, &Side, &mut AttackState), With<Player>>,
+    action_state: Res<ActionState>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &Side,
+            &Stats,
+            Option<&PlayerModifiers>,
+            &mut AttackState,
+        ),
+        With<Player>,
+    >,
     mut commands: Commands,
 ) {
-    for (player_entity, transform, side, mut attack_state) in query.iter_mut() {
-        // Check attack input from gamepad (priority) or keyboard
-        let attack_pressed = if let Some(gamepad_entity) = gamepad_state.active_gamepad {
-            // Gamepad: use West button (X/Square) for attack
-            is_button_just_pressed(&gamepads, gamepad_entity, GamepadButton::West)
-        } else {
-            // Keyboard fallback
-            input.just_pressed(key_bindings.attack)
-        };
+    for (player_entity, transform, side, stats, modifiers, mut attack_state) in query.iter_mut() {
+        let attack_pressed = action_state.just_pressed(Action::Attack);
 
         if attack_pressed && attack_state.can_attack && !attack_state.is_attacking {
             attack_state.is_attacking = true;
@@ -105,8 +108,9 @@ pub fn handle_attack_input(
 
             // Créer le slash visuel avec animation
             commands.spawn((
+                StateScoped(InGameplay),
                 AttackHitbox {
-                    damage: 1,
+                    damage: stats.damage + modifiers.map_or(0, |modifiers| modifiers.attack_bonus),
                     owner: player_entity,
                 },
                 Sprite {
@@ -205,7 +209,8 @@ pub fn update_attack_state(
 pub fn detect_attack_hits(
     mut collision_events: EventReader<CollisionEvent>,
     hitbox_query: Query<(&AttackHitbox, &Transform)>,
-    target_query: Query<&Transform>,
+    target_query: Query<(&Transform, Has<Stats>)>,
+    movement_query: Query<&Movement>,
     mut damage_events: EventWriter<DamageEvent>,
     mut hit_events: EventWriter<AttackHitEvent>,
 ) {
@@ -227,22 +232,31 @@ pub fn detect_attack_hits(
                 }
 
                 // Vérifier que la cible existe
-                if let Ok(target_transform) = target_query.get(other_entity) {
+                if let Ok((target_transform, damageable)) = target_query.get(other_entity) {
                     // Calculer la direction du knockback
                     let knockback_direction = (target_transform.translation
                         - hitbox_transform.translation)
                         .truncate()
                         .normalize_or_zero();
 
-                    hit_events.send(AttackHitEvent {
-                        attacker: hitbox.owner,
-                        target: other_entity,
-                        damage: hitbox.damage,
-                    });
+                    let knockback_force = movement_query
+                        .get(hitbox.owner)
+                        .map_or(Movement::default().attack_knockback, |movement| {
+                            movement.attack_knockback
+                        });
+
+                    // Only the hits on something that can take damage are reported
+                    if damageable {
+                        hit_events.send(AttackHitEvent {
+                            attacker: hitbox.owner,
+                            target: other_entity,
+                            damage: hitbox.damage,
+                        });
+                    }
 
                     damage_events.send(
                         DamageEvent::new(other_entity, hitbox.owner, hitbox.damage)
-                            .with_knockback(knockback_direction, 350.0),
+                            .with_knockback(knockback_direction, knockback_force),
                     );
                 }
             }
@@ -262,7 +276,8 @@ impl Plugin for AttackPlugin {
                 animate_slash,
                 update_attack_state,
                 detect_attack_hits,
-            ),
+            )
+                .run_if(in_state(GameState::InGame)),
         );
     }
 }
@@ -1,10 +1,17 @@
+use bevy::input::keyboard::NativeKeyCode;
 use bevy::prelude::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-/// Serializable key binding storage (uses strings)
+use crate::audio::AudioChannel;
+use crate::input::actions::{Action, ActionMap};
+
+/// Serializable key binding storage (uses strings): the keyboard key of each action
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct KeyBindingsConfig {
     pub move_left: String,
     pub move_right: String,
@@ -13,6 +20,7 @@ pub struct KeyBindingsConfig {
     pub jump: String,
     pub attack: String,
     pub interact: String,
+    pub inventory: String,
 }
 
 impl Default for KeyBindingsConfig {
@@ -25,273 +33,618 @@ impl Default for KeyBindingsConfig {
             jump: "Space".to_string(),
             attack: "KeyK".to_string(),
             interact: "KeyO".to_string(),
+            inventory: "KeyI".to_string(),
+        }
+    }
+}
+
+impl From<&KeyBindingsConfig> for ActionMap {
+    fn from(config: &KeyBindingsConfig) -> Self {
+        let mut action_map = ActionMap::default();
+        for action in Action::ALL {
+            match string_to_keycode(config.key(action)) {
+                Ok(key) => action_map.set_key(action, key),
+                Err(e) => warn!("{} for {:?}, keeping the default", e, action),
+            }
+        }
+        action_map
+    }
+}
+
+impl From<&ActionMap> for KeyBindingsConfig {
+    fn from(action_map: &ActionMap) -> Self {
+        let mut config = KeyBindingsConfig::default();
+        for action in Action::ALL {
+            if let Some(key) = action_map.key(action) {
+                *config.key_mut(action) = keycode_to_config_string(key);
+            }
         }
+        config
     }
 }
 
-/// Runtime key bindings with actual KeyCode values
-#[derive(Debug, Clone, Resource)]
-pub struct KeyBindings {
-    pub move_left: KeyCode,
-    pub move_right: KeyCode,
-    pub move_up: KeyCode,
-    pub move_down: KeyCode,
-    pub jump: KeyCode,
-    pub attack: KeyCode,
-    pub interact: KeyCode,
+impl KeyBindingsConfig {
+    fn key(&self, action: Action) -> &str {
+        match action {
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::MoveUp => &self.move_up,
+            Action::MoveDown => &self.move_down,
+            Action::Jump => &self.jump,
+            Action::Attack => &self.attack,
+            Action::Interact => &self.interact,
+            Action::Inventory => &self.inventory,
+        }
+    }
+
+    fn key_mut(&mut self, action: Action) -> &mut String {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::MoveUp => &mut self.move_up,
+            Action::MoveDown => &mut self.move_down,
+            Action::Jump => &mut self.jump,
+            Action::Attack => &mut self.attack,
+            Action::Interact => &mut self.interact,
+            Action::Inventory => &mut self.inventory,
+        }
+    }
+}
+
+/// Serializable gamepad button storage (uses strings): the gamepad button of each action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadBindingsConfig {
+    pub move_left: String,
+    pub move_right: String,
+    pub move_up: String,
+    pub move_down: String,
+    pub jump: String,
+    pub attack: String,
+    pub interact: String,
+    pub inventory: String,
 }
 
-impl Default for KeyBindings {
+impl Default for GamepadBindingsConfig {
     fn default() -> Self {
+        Self::from(&ActionMap::default())
+    }
+}
+
+impl From<&ActionMap> for GamepadBindingsConfig {
+    fn from(action_map: &ActionMap) -> Self {
+        let button = |action| {
+            action_map
+                .gamepad_button(action)
+                .map(gamepad_button_to_config_string)
+                .unwrap_or_default()
+        };
         Self {
-            move_left: KeyCode::KeyA,
-            move_right: KeyCode::KeyD,
-            move_up: KeyCode::KeyW,
-            move_down: KeyCode::KeyS,
-            jump: KeyCode::Space,
-            attack: KeyCode::KeyK,
-            interact: KeyCode::KeyO,
+            move_left: button(Action::MoveLeft),
+            move_right: button(Action::MoveRight),
+            move_up: button(Action::MoveUp),
+            move_down: button(Action::MoveDown),
+            jump: button(Action::Jump),
+            attack: button(Action::Attack),
+            interact: button(Action::Interact),
+            inventory: button(Action::Inventory),
         }
     }
 }
 
-impl From<&KeyBindingsConfig> for KeyBindings {
-    fn from(config: &KeyBindingsConfig) -> Self {
-        Self {
-            move_left: string_to_keycode(&config.move_left),
-            move_right: string_to_keycode(&config.move_right),
-            move_up: string_to_keycode(&config.move_up),
-            move_down: string_to_keycode(&config.move_down),
-            jump: string_to_keycode(&config.jump),
-            attack: string_to_keycode(&config.attack),
-            interact: string_to_keycode(&config.interact),
+impl GamepadBindingsConfig {
+    fn button(&self, action: Action) -> &str {
+        match action {
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::MoveUp => &self.move_up,
+            Action::MoveDown => &self.move_down,
+            Action::Jump => &self.jump,
+            Action::Attack => &self.attack,
+            Action::Interact => &self.interact,
+            Action::Inventory => &self.inventory,
+        }
+    }
+
+    /// Apply the stored buttons to an action map (unknown buttons keep the default one)
+    pub fn apply(&self, action_map: &mut ActionMap) {
+        for action in Action::ALL {
+            match string_to_gamepad_button(self.button(action)) {
+                Some(button) => action_map.set_gamepad_button(action, button),
+                None if self.button(action).is_empty() => {}
+                None => warn!(
+                    "Unknown gamepad button {:?} for {:?}, keeping the default",
+                    self.button(action),
+                    action
+                ),
+            }
         }
     }
 }
 
-impl From<&KeyBindings> for KeyBindingsConfig {
-    fn from(bindings: &KeyBindings) -> Self {
-        Self {
-            move_left: keycode_to_config_string(bindings.move_left),
-            move_right: keycode_to_config_string(bindings.move_right),
-            move_up: keycode_to_config_string(bindings.move_up),
-            move_down: keycode_to_config_string(bindings.move_down),
-            jump: keycode_to_config_string(bindings.jump),
-            attack: keycode_to_config_string(bindings.attack),
-            interact: keycode_to_config_string(bindings.interact),
-        }
-    }
-}
-
-/// Convert a string to KeyCode
-pub fn string_to_keycode(s: &str) -> KeyCode {
-    match s {
-        "KeyA" => KeyCode::KeyA,
-        "KeyB" => KeyCode::KeyB,
-        "KeyC" => KeyCode::KeyC,
-        "KeyD" => KeyCode::KeyD,
-        "KeyE" => KeyCode::KeyE,
-        "KeyF" => KeyCode::KeyF,
-        "KeyG" => KeyCode::KeyG,
-        "KeyH" => KeyCode::KeyH,
-        "KeyI" => KeyCode::KeyI,
-        "KeyJ" => KeyCode::KeyJ,
-        "KeyK" => KeyCode::KeyK,
-        "KeyL" => KeyCode::KeyL,
-        "KeyM" => KeyCode::KeyM,
-        "KeyN" => KeyCode::KeyN,
-        "KeyO" => KeyCode::KeyO,
-        "KeyP" => KeyCode::KeyP,
-        "KeyQ" => KeyCode::KeyQ,
-        "KeyR" => KeyCode::KeyR,
-        "KeyS" => KeyCode::KeyS,
-        "KeyT" => KeyCode::KeyT,
-        "KeyU" => KeyCode::KeyU,
-        "KeyV" => KeyCode::KeyV,
-        "KeyW" => KeyCode::KeyW,
-        "KeyX" => KeyCode::KeyX,
-        "KeyY" => KeyCode::KeyY,
-        "KeyZ" => KeyCode::KeyZ,
-        "Space" => KeyCode::Space,
-        "Enter" => KeyCode::Enter,
-        "Escape" => KeyCode::Escape,
-        "ArrowUp" => KeyCode::ArrowUp,
-        "ArrowDown" => KeyCode::ArrowDown,
-        "ArrowLeft" => KeyCode::ArrowLeft,
-        "ArrowRight" => KeyCode::ArrowRight,
-        "ShiftLeft" => KeyCode::ShiftLeft,
-        "ShiftRight" => KeyCode::ShiftRight,
-        "ControlLeft" => KeyCode::ControlLeft,
-        "ControlRight" => KeyCode::ControlRight,
-        "AltLeft" => KeyCode::AltLeft,
-        "AltRight" => KeyCode::AltRight,
-        "Tab" => KeyCode::Tab,
-        "Digit1" => KeyCode::Digit1,
-        "Digit2" => KeyCode::Digit2,
-        "Digit3" => KeyCode::Digit3,
-        "Digit4" => KeyCode::Digit4,
-        "Digit5" => KeyCode::Digit5,
-        "Digit6" => KeyCode::Digit6,
-        "Digit7" => KeyCode::Digit7,
-        "Digit8" => KeyCode::Digit8,
-        "Digit9" => KeyCode::Digit9,
-        "Digit0" => KeyCode::Digit0,
-        _ => KeyCode::KeyA, // Default fallback
+/// Every named `GamepadButton`, stored in the config under its variant name.
+///
+/// As for the keys, the match is exhaustive so that every button the settings menu captures
+/// can be stored.
+macro_rules! named_buttons {
+    ($($button:ident),* $(,)?) => {
+        /// Every `GamepadButton` but `GamepadButton::Other`
+        #[cfg(test)]
+        pub const NAMED_BUTTONS: &[GamepadButton] = &[$(GamepadButton::$button),*];
+
+        fn named_button_to_str(button: GamepadButton) -> Option<&'static str> {
+            match button {
+                $(GamepadButton::$button => Some(stringify!($button)),)*
+                GamepadButton::Other(_) => None,
+            }
+        }
+
+        fn str_to_named_button(s: &str) -> Option<GamepadButton> {
+            match s {
+                $(stringify!($button) => Some(GamepadButton::$button),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+named_buttons! {
+    South, East, North, West, C, Z,
+    LeftTrigger, LeftTrigger2, RightTrigger, RightTrigger2,
+    Select, Start, Mode, LeftThumb, RightThumb,
+    DPadUp, DPadDown, DPadLeft, DPadRight,
+}
+
+/// Convert a string to GamepadButton
+pub fn string_to_gamepad_button(s: &str) -> Option<GamepadButton> {
+    str_to_named_button(s).or_else(|| {
+        s.strip_prefix("Other(")
+            .and_then(|index| index.strip_suffix(')'))
+            .and_then(|index| index.parse().ok())
+            .map(GamepadButton::Other)
+    })
+}
+
+/// Convert GamepadButton to config string
+pub fn gamepad_button_to_config_string(button: GamepadButton) -> String {
+    match named_button_to_str(button) {
+        Some(name) => name.to_string(),
+        // Same format as Debug, e.g. `Other(12)`
+        None => format!("{:?}", button),
     }
 }
 
+/// Every named `KeyCode`, stored in the config under its variant name.
+///
+/// The match on the key is exhaustive: a key added to Bevy does not compile until it is
+/// listed here, so the config can always store what the settings menu captures.
+macro_rules! named_keys {
+    ($($key:ident),* $(,)?) => {
+        /// Every `KeyCode` but `KeyCode::Unidentified`
+        #[cfg(test)]
+        pub const NAMED_KEYS: &[KeyCode] = &[$(KeyCode::$key),*];
+
+        fn named_key_to_str(key: KeyCode) -> Option<&'static str> {
+            match key {
+                $(KeyCode::$key => Some(stringify!($key)),)*
+                KeyCode::Unidentified(_) => None,
+            }
+        }
+
+        fn str_to_named_key(s: &str) -> Option<KeyCode> {
+            match s {
+                $(stringify!($key) => Some(KeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+named_keys! {
+    // Writing system keys (AZERTY letters, digits and punctuation are physical positions)
+    Backquote, Backslash, BracketLeft, BracketRight, Comma,
+    Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    Equal, IntlBackslash, IntlRo, IntlYen,
+    KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+    KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    Minus, Period, Quote, Semicolon, Slash,
+    // Functional keys
+    AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft, ControlRight, Enter,
+    SuperLeft, SuperRight, ShiftLeft, ShiftRight, Space, Tab,
+    Convert, KanaMode, Lang1, Lang2, Lang3, Lang4, Lang5, NonConvert,
+    // Control pad and arrows
+    Delete, End, Help, Home, Insert, PageDown, PageUp,
+    ArrowDown, ArrowLeft, ArrowRight, ArrowUp,
+    // Numpad
+    NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7,
+    Numpad8, Numpad9, NumpadAdd, NumpadBackspace, NumpadClear, NumpadClearEntry,
+    NumpadComma, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadEqual, NumpadHash,
+    NumpadMemoryAdd, NumpadMemoryClear, NumpadMemoryRecall, NumpadMemoryStore,
+    NumpadMemorySubtract, NumpadMultiply, NumpadParenLeft, NumpadParenRight, NumpadStar,
+    NumpadSubtract,
+    // Function section and media keys
+    Escape, Fn, FnLock, PrintScreen, ScrollLock, Pause,
+    BrowserBack, BrowserFavorites, BrowserForward, BrowserHome, BrowserRefresh,
+    BrowserSearch, BrowserStop, Eject, LaunchApp1, LaunchApp2, LaunchMail,
+    MediaPlayPause, MediaSelect, MediaStop, MediaTrackNext, MediaTrackPrevious,
+    Power, Sleep, AudioVolumeDown, AudioVolumeMute, AudioVolumeUp, WakeUp,
+    Meta, Hyper, Turbo, Abort, Resume, Suspend, Again, Copy, Cut, Find, Open, Paste,
+    Props, Select, Undo, Hiragana, Katakana,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18,
+    F19, F20, F21, F22, F23, F24, F25, F26, F27, F28, F29, F30, F31, F32, F33, F34, F35,
+}
+
+/// Parse the platform code of an unidentified key, written as `Unidentified(Xkb(38))`
+fn string_to_native_keycode(s: &str) -> Option<NativeKeyCode> {
+    if s == "Unidentified" {
+        return Some(NativeKeyCode::Unidentified);
+    }
+    let (platform, code) = s.strip_suffix(')')?.split_once('(')?;
+    let native = match platform {
+        "Android" => NativeKeyCode::Android(code.parse().ok()?),
+        "MacOS" => NativeKeyCode::MacOS(code.parse().ok()?),
+        "Windows" => NativeKeyCode::Windows(code.parse().ok()?),
+        "Xkb" => NativeKeyCode::Xkb(code.parse().ok()?),
+        _ => return None,
+    };
+    Some(native)
+}
+
+/// Convert a config string to KeyCode
+pub fn string_to_keycode(s: &str) -> Result<KeyCode, String> {
+    str_to_named_key(s)
+        .or_else(|| {
+            s.strip_prefix("Unidentified(")
+                .and_then(|native| native.strip_suffix(')'))
+                .and_then(string_to_native_keycode)
+                .map(KeyCode::Unidentified)
+        })
+        .ok_or_else(|| format!("Unknown key {:?}", s))
+}
+
 /// Convert KeyCode to config string
 pub fn keycode_to_config_string(key: KeyCode) -> String {
-    match key {
-        KeyCode::KeyA => "KeyA".to_string(),
-        KeyCode::KeyB => "KeyB".to_string(),
-        KeyCode::KeyC => "KeyC".to_string(),
-        KeyCode::KeyD => "KeyD".to_string(),
-        KeyCode::KeyE => "KeyE".to_string(),
-        KeyCode::KeyF => "KeyF".to_string(),
-        KeyCode::KeyG => "KeyG".to_string(),
-        KeyCode::KeyH => "KeyH".to_string(),
-        KeyCode::KeyI => "KeyI".to_string(),
-        KeyCode::KeyJ => "KeyJ".to_string(),
-        KeyCode::KeyK => "KeyK".to_string(),
-        KeyCode::KeyL => "KeyL".to_string(),
-        KeyCode::KeyM => "KeyM".to_string(),
-        KeyCode::KeyN => "KeyN".to_string(),
-        KeyCode::KeyO => "KeyO".to_string(),
-        KeyCode::KeyP => "KeyP".to_string(),
-        KeyCode::KeyQ => "KeyQ".to_string(),
-        KeyCode::KeyR => "KeyR".to_string(),
-        KeyCode::KeyS => "KeyS".to_string(),
-        KeyCode::KeyT => "KeyT".to_string(),
-        KeyCode::KeyU => "KeyU".to_string(),
-        KeyCode::KeyV => "KeyV".to_string(),
-        KeyCode::KeyW => "KeyW".to_string(),
-        KeyCode::KeyX => "KeyX".to_string(),
-        KeyCode::KeyY => "KeyY".to_string(),
-        KeyCode::KeyZ => "KeyZ".to_string(),
-        KeyCode::Space => "Space".to_string(),
-        KeyCode::Enter => "Enter".to_string(),
-        KeyCode::Escape => "Escape".to_string(),
-        KeyCode::ArrowUp => "ArrowUp".to_string(),
-        KeyCode::ArrowDown => "ArrowDown".to_string(),
-        KeyCode::ArrowLeft => "ArrowLeft".to_string(),
-        KeyCode::ArrowRight => "ArrowRight".to_string(),
-        KeyCode::ShiftLeft => "ShiftLeft".to_string(),
-        KeyCode::ShiftRight => "ShiftRight".to_string(),
-        KeyCode::ControlLeft => "ControlLeft".to_string(),
-        KeyCode::ControlRight => "ControlRight".to_string(),
-        KeyCode::AltLeft => "AltLeft".to_string(),
-        KeyCode::AltRight => "AltRight".to_string(),
-        KeyCode::Tab => "Tab".to_string(),
-        KeyCode::Digit1 => "Digit1".to_string(),
-        KeyCode::Digit2 => "Digit2".to_string(),
-        KeyCode::Digit3 => "Digit3".to_string(),
-        KeyCode::Digit4 => "Digit4".to_string(),
-        KeyCode::Digit5 => "Digit5".to_string(),
-        KeyCode::Digit6 => "Digit6".to_string(),
-        KeyCode::Digit7 => "Digit7".to_string(),
-        KeyCode::Digit8 => "Digit8".to_string(),
-        KeyCode::Digit9 => "Digit9".to_string(),
-        KeyCode::Digit0 => "Digit0".to_string(),
-        _ => format!("{:?}", key),
+    match named_key_to_str(key) {
+        Some(name) => name.to_string(),
+        // Same format as Debug, e.g. `Unidentified(Xkb(38))`
+        None => format!("{:?}", key),
     }
 }
 
 /// Audio configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
 pub struct AudioConfig {
     /// Master volume (0.0 to 1.0)
     pub master_volume: f32,
+    /// Background music volume (0.0 to 1.0), on top of the master volume
+    pub music_volume: f32,
+    /// Sound effects volume (0.0 to 1.0), on top of the master volume
+    pub sfx_volume: f32,
 }
 
 impl Default for AudioConfig {
     fn default() -> Self {
         Self {
             master_volume: 0.35, // 35% by default
+            music_volume: 0.8,
+            sfx_volume: 1.0,
+        }
+    }
+}
+
+impl AudioConfig {
+    pub fn volume(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Master => self.master_volume,
+            AudioChannel::Music => self.music_volume,
+            AudioChannel::Sfx => self.sfx_volume,
+        }
+    }
+
+    pub fn volume_mut(&mut self, channel: AudioChannel) -> &mut f32 {
+        match channel {
+            AudioChannel::Master => &mut self.master_volume,
+            AudioChannel::Music => &mut self.music_volume,
+            AudioChannel::Sfx => &mut self.sfx_volume,
+        }
+    }
+}
+
+/// How the game window covers the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    /// Fullscreen window without borders, keeping the desktop resolution
+    Borderless,
+    /// Exclusive fullscreen
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub const ALL: [WindowModeSetting; 3] = [
+        WindowModeSetting::Windowed,
+        WindowModeSetting::Borderless,
+        WindowModeSetting::Fullscreen,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "Fenêtré",
+            WindowModeSetting::Borderless => "Sans bordure",
+            WindowModeSetting::Fullscreen => "Plein écran",
         }
     }
 }
 
+/// Smallest window accepted from the config file
+pub const MIN_RESOLUTION: UVec2 = UVec2::new(640, 360);
+
+/// Display configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub window_mode: WindowModeSetting,
+    /// Size of the window in windowed mode (in logical pixels)
+    pub resolution: UVec2,
+    pub vsync: bool,
+    /// Scale the pixel art by whole factors only, so that every texel stays square
+    pub pixel_scaling: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowModeSetting::Windowed,
+            resolution: UVec2::new(1080, 720),
+            vsync: true,
+            pixel_scaling: false,
+        }
+    }
+}
+
+/// Version of the config file format, bumped whenever a change of `GameConfigData` needs
+/// more than the serde defaults to read older files
+pub const CONFIG_VERSION: u32 = 1;
+
 /// Serializable game configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameConfigData {
+    pub version: u32,
     pub key_bindings: KeyBindingsConfig,
+    pub gamepad_bindings: GamepadBindingsConfig,
     pub audio: AudioConfig,
+    pub display: DisplayConfig,
 }
 
 impl Default for GameConfigData {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             key_bindings: KeyBindingsConfig::default(),
+            gamepad_bindings: GamepadBindingsConfig::default(),
             audio: AudioConfig::default(),
+            display: DisplayConfig::default(),
         }
     }
 }
 
-/// Runtime game configuration resource
-#[derive(Debug, Clone, Resource)]
-pub struct GameConfig {
-    pub key_bindings: KeyBindings,
-    pub audio: AudioConfig,
+impl From<&GameConfig> for GameConfigData {
+    fn from(config: &GameConfig) -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            key_bindings: KeyBindingsConfig::from(&config.actions),
+            gamepad_bindings: GamepadBindingsConfig::from(&config.actions),
+            audio: config.audio.clone(),
+            display: config.display.clone(),
+        }
+    }
 }
 
-impl Default for GameConfig {
-    fn default() -> Self {
-        Self {
-            key_bindings: KeyBindings::default(),
-            audio: AudioConfig::default(),
+impl GameConfigData {
+    /// Read a config file, upgrading it to the current format first.
+    ///
+    /// Missing settings take their default value, and a section that does not parse falls
+    /// back to its defaults without wiping the other ones.
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| format!("Failed to parse config file: {}", e))?;
+        let value = Self::migrate(value)?;
+
+        let mut audio: AudioConfig = config_section(&value, "audio");
+        for channel in AudioChannel::ALL {
+            let volume = audio.volume_mut(channel);
+            *volume = volume.clamp(0.0, 1.0);
         }
+
+        let mut display: DisplayConfig = config_section(&value, "display");
+        display.resolution = display.resolution.max(MIN_RESOLUTION);
+
+        Ok(Self {
+            version: CONFIG_VERSION,
+            key_bindings: config_section(&value, "key_bindings"),
+            gamepad_bindings: config_section(&value, "gamepad_bindings"),
+            audio,
+            display,
+        })
     }
+
+    /// Upgrade a config written by an older version of the game to the current format
+    fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+        if !value.is_object() {
+            return Err("Config file is not a JSON object".to_string());
+        }
+
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0) as u32;
+
+        if version > CONFIG_VERSION {
+            return Err(format!(
+                "Config file version {} is newer than the supported version {}",
+                version, CONFIG_VERSION
+            ));
+        }
+
+        // v0 files only lack the version: their missing settings are filled by serde
+
+        value["version"] = CONFIG_VERSION.into();
+        Ok(value)
+    }
+}
+
+/// Parse one section of the config file, its defaults if it is missing or invalid
+fn config_section<T: DeserializeOwned + Default>(value: &serde_json::Value, name: &str) -> T {
+    let Some(section) = value.get(name) else {
+        return T::default();
+    };
+    serde_json::from_value(section.clone()).unwrap_or_else(|e| {
+        warn!("Invalid {} in config file: {}. Using defaults.", name, e);
+        T::default()
+    })
+}
+
+/// Write a file through a temporary file renamed over it, so that a crash while writing
+/// never leaves a truncated file behind
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Folder of the game in the app storage
+#[cfg(target_os = "android")]
+fn platform_config_dir() -> Option<PathBuf> {
+    bevy::window::ANDROID_APP.get()?.internal_data_path()
+}
+
+/// Folder of the game in `%APPDATA%`
+#[cfg(target_os = "windows")]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("TheSeventh"))
+}
+
+/// Folder of the game in `~/Library/Application Support`
+#[cfg(target_os = "macos")]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("TheSeventh")
+    })
+}
+
+/// Folder of the game in `$XDG_CONFIG_HOME`, `~/.config` when unset
+#[cfg(not(any(target_os = "android", target_os = "windows", target_os = "macos")))]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("theseventh"))
+}
+
+/// Runtime game configuration resource
+#[derive(Debug, Clone, Default, Resource)]
+pub struct GameConfig {
+    pub actions: ActionMap,
+    pub audio: AudioConfig,
+    pub display: DisplayConfig,
 }
 
 impl From<GameConfigData> for GameConfig {
     fn from(data: GameConfigData) -> Self {
+        let mut actions = ActionMap::from(&data.key_bindings);
+        data.gamepad_bindings.apply(&mut actions);
         Self {
-            key_bindings: KeyBindings::from(&data.key_bindings),
+            actions,
             audio: data.audio,
+            display: data.display,
         }
     }
 }
 
 impl GameConfig {
+    /// Get the directory holding the configuration file (save files live next to it), the
+    /// working directory when the platform has none
+    pub fn config_dir() -> PathBuf {
+        platform_config_dir().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Get the path of a file of the config directory.
+    ///
+    /// Older versions of the game wrote their files in the working directory: such a file is
+    /// moved to the config directory the first time it is looked up.
+    pub fn data_path(name: &str) -> PathBuf {
+        let path = Self::config_dir().join(name);
+        let legacy_path = PathBuf::from(name);
+        if path.exists() || !legacy_path.is_file() {
+            return path;
+        }
+
+        let moved = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::rename(&legacy_path, &path))
+            // Another file system: keep the old file around
+            .or_else(|_| fs::copy(&legacy_path, &path).map(|_| ()));
+        match moved {
+            Ok(_) => info!("Moved {:?} to {:?}", legacy_path, path),
+            Err(e) => {
+                warn!("Failed to move {:?} to {:?}: {}", legacy_path, path, e);
+                return legacy_path;
+            }
+        }
+        path
+    }
+
     /// Get the configuration file path
     fn config_path() -> PathBuf {
-        PathBuf::from("config.json")
+        Self::data_path("config.json")
     }
 
     /// Load configuration from file, or return default if file doesn't exist
     pub fn load() -> Self {
         let path = Self::config_path();
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => match serde_json::from_str::<GameConfigData>(&content) {
-                    Ok(data) => {
-                        info!("Configuration loaded from {:?}", path);
-                        return GameConfig::from(data);
-                    }
-                    Err(e) => {
-                        warn!("Failed to parse config file: {}. Using defaults.", e);
-                    }
-                },
-                Err(e) => {
-                    warn!("Failed to read config file: {}. Using defaults.", e);
-                }
-            }
-        } else {
+        if !path.exists() {
             info!("No config file found. Using defaults.");
+            return Self::default();
+        }
+
+        let data = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read config file: {}", e))
+            .and_then(|content| GameConfigData::from_json(&content));
+        match data {
+            Ok(data) => {
+                info!("Configuration loaded from {:?}", path);
+                GameConfig::from(data)
+            }
+            Err(e) => {
+                warn!("{}. Using defaults.", e);
+                Self::default()
+            }
         }
-        Self::default()
     }
 
     /// Save configuration to file
     pub fn save(&self) -> Result<(), String> {
         let path = Self::config_path();
-        let data = GameConfigData {
-            key_bindings: KeyBindingsConfig::from(&self.key_bindings),
-            audio: self.audio.clone(),
-        };
+        let data = GameConfigData::from(self);
         match serde_json::to_string_pretty(&data) {
-            Ok(content) => match fs::write(&path, content) {
+            Ok(content) => match write_atomically(&path, &content) {
                 Ok(_) => {
                     info!("Configuration saved to {:?}", path);
                     Ok(())
@@ -312,9 +665,162 @@ impl Plugin for ConfigPlugin {
         let volume = config.audio.master_volume;
 
         // Insert resources
-        app.insert_resource(config.key_bindings.clone())
+        app.insert_resource(config.actions.clone())
             .insert_resource(config.audio.clone())
+            .insert_resource(config.display.clone())
             .insert_resource(config)
             .insert_resource(GlobalVolume::new(volume));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::actions::InputBinding;
+
+    #[test]
+    fn config_without_gamepad_bindings_keeps_the_default_buttons() {
+        let json = r#"{
+            "key_bindings": {
+                "move_left": "KeyA", "move_right": "KeyD", "move_up": "KeyW",
+                "move_down": "KeyS", "jump": "Space", "attack": "KeyK", "interact": "KeyO"
+            },
+            "audio": { "master_volume": 0.5 }
+        }"#;
+        let data = GameConfigData::from_json(json).unwrap();
+        assert_eq!(data.version, CONFIG_VERSION);
+
+        let config = GameConfig::from(data);
+        assert_eq!(config.actions, ActionMap::default());
+    }
+
+    #[test]
+    fn config_from_a_newer_version_is_rejected() {
+        let json = format!(r#"{{ "version": {} }}"#, CONFIG_VERSION + 1);
+        assert!(GameConfigData::from_json(&json).is_err());
+        assert!(GameConfigData::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn invalid_section_keeps_the_other_settings() {
+        let json = r#"{
+            "version": 1,
+            "key_bindings": { "jump": "KeyJ" },
+            "gamepad_bindings": 42,
+            "audio": { "master_volume": 3.0, "unknown_setting": true }
+        }"#;
+        let config = GameConfig::from(GameConfigData::from_json(json).unwrap());
+
+        assert_eq!(config.actions.key(Action::Jump), Some(KeyCode::KeyJ));
+        assert_eq!(config.actions.key(Action::Attack), Some(KeyCode::KeyK));
+        assert_eq!(
+            config.actions.gamepad_button(Action::Jump),
+            Some(GamepadButton::South)
+        );
+        assert_eq!(config.audio.master_volume, 1.0);
+    }
+
+    #[test]
+    fn atomic_write_replaces_the_file_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("theseventh-config-{}", std::process::id()));
+        let path = dir.join("config.json");
+
+        write_atomically(&path, "first").unwrap();
+        write_atomically(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remapped_gamepad_buttons_survive_a_save() {
+        let mut actions = ActionMap::default();
+        actions.set_gamepad_button(Action::Jump, GamepadButton::RightTrigger);
+        let data = GameConfigData::from(&GameConfig {
+            actions,
+            ..default()
+        });
+
+        let json = serde_json::to_string(&data).unwrap();
+        let config = GameConfig::from(GameConfigData::from_json(&json).unwrap());
+
+        assert_eq!(
+            config.actions.gamepad_button(Action::Jump),
+            Some(GamepadButton::RightTrigger)
+        );
+        // The left stick still moves the player
+        assert_eq!(config.actions.bindings(Action::MoveLeft).len(), 3);
+    }
+
+    #[test]
+    fn actions_sharing_a_button_are_reported_as_conflicts() {
+        let mut actions = ActionMap::default();
+        assert!(actions.conflicts().is_empty());
+
+        actions.set_gamepad_button(Action::Attack, GamepadButton::South);
+
+        assert_eq!(
+            actions.conflicts(),
+            vec![(
+                InputBinding::GamepadButton(GamepadButton::South),
+                vec![Action::Jump, Action::Attack]
+            )]
+        );
+    }
+
+    #[test]
+    fn every_named_key_round_trips() {
+        for key in NAMED_KEYS {
+            let name = keycode_to_config_string(*key);
+            assert_eq!(string_to_keycode(&name), Ok(*key), "{name}");
+        }
+    }
+
+    #[test]
+    fn every_gamepad_button_round_trips() {
+        let others = (0..=u8::MAX).map(GamepadButton::Other);
+        for button in NAMED_BUTTONS.iter().copied().chain(others) {
+            let name = gamepad_button_to_config_string(button);
+            assert_eq!(string_to_gamepad_button(&name), Some(button), "{name}");
+        }
+        assert_eq!(string_to_gamepad_button("Other(256)"), None);
+    }
+
+    #[test]
+    fn unidentified_keys_round_trip() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut natives = vec![NativeKeyCode::Unidentified];
+        for _ in 0..256 {
+            natives.push(NativeKeyCode::Android(rng.gen()));
+            natives.push(NativeKeyCode::MacOS(rng.gen()));
+            natives.push(NativeKeyCode::Windows(rng.gen()));
+            natives.push(NativeKeyCode::Xkb(rng.gen()));
+        }
+
+        for native in natives {
+            let key = KeyCode::Unidentified(native);
+            let name = keycode_to_config_string(key);
+            assert_eq!(string_to_keycode(&name), Ok(key), "{name}");
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_and_keep_the_default() {
+        assert!(string_to_keycode("").is_err());
+        assert!(string_to_keycode("KeyÉ").is_err());
+        assert!(string_to_keycode("Unidentified(Xkb(-1))").is_err());
+
+        let config = KeyBindingsConfig {
+            jump: "NotAKey".to_string(),
+            attack: "F5".to_string(),
+            ..default()
+        };
+        let actions = ActionMap::from(&config);
+
+        assert_eq!(actions.key(Action::Jump), Some(KeyCode::Space));
+        assert_eq!(actions.key(Action::Attack), Some(KeyCode::F5));
+    }
+}
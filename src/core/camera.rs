@@ -1,25 +1,92 @@
 use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::window::PrimaryWindow;
 use bevy_ecs_ldtk::prelude::*;
 
+use crate::config::DisplayConfig;
 use crate::entities::player::Player;
 
-const ASPECT_RATIO: f32 = 16. / 9.;
+/// Narrowest and widest aspect ratios of the game view: beyond them the view is letterboxed
+/// (or pillarboxed) instead of showing a sliver of the level
+pub const MIN_ASPECT_RATIO: f32 = 4. / 3.;
+pub const MAX_ASPECT_RATIO: f32 = 21. / 9.;
+
+/// Camera of the game view, fitted inside the current level
+#[derive(Component)]
+pub struct GameCamera;
+
+/// Camera drawing the black bars around a letterboxed view (it renders nothing else)
+#[derive(Component)]
+pub struct LetterboxCamera;
+
+/// Where the game is drawn in the window and how much of the level it shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewFit {
+    /// Position of the viewport in the window (in physical pixels)
+    pub viewport_position: UVec2,
+    /// Size of the viewport (in physical pixels)
+    pub viewport_size: UVec2,
+    /// Size of the visible part of the level (in level pixels)
+    pub view_size: Vec2,
+}
+
+/// Fit the view inside the level for any window size.
+///
+/// The view keeps the aspect ratio of the window (clamped between `MIN_ASPECT_RATIO` and
+/// `MAX_ASPECT_RATIO`) and covers the whole level along its shortest side. With
+/// `pixel_scaling`, the level is magnified by the smallest whole factor that still fills
+/// the viewport instead.
+pub fn fit_view(window_size: UVec2, level_size: Vec2, pixel_scaling: bool) -> ViewFit {
+    let window = window_size.max(UVec2::ONE).as_vec2();
+    let aspect = (window.x / window.y).clamp(MIN_ASPECT_RATIO, MAX_ASPECT_RATIO);
+    let viewport = if window.x / window.y > aspect {
+        // Bars on the sides
+        Vec2::new(window.y * aspect, window.y)
+    } else {
+        // Bars above and below
+        Vec2::new(window.x, window.x / aspect)
+    };
+    let viewport_size = viewport.round().as_uvec2().max(UVec2::ONE);
+    let viewport_position = window_size.saturating_sub(viewport_size) / 2;
+
+    let view_size = if pixel_scaling {
+        let viewport = viewport_size.as_vec2();
+        let scale = (viewport / level_size).max_element().ceil().max(1.);
+        viewport / scale
+    } else if level_size.x / level_size.y > aspect {
+        // level is wider than the screen
+        Vec2::new(level_size.y * aspect, level_size.y)
+    } else {
+        // level is taller than the screen
+        Vec2::new(level_size.x, level_size.x / aspect)
+    };
+
+    ViewFit {
+        viewport_position,
+        viewport_size,
+        view_size,
+    }
+}
 
 #[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
 pub fn camera_fit_inside_current_level(
     mut camera_query: Query<
-        (
-            &mut bevy::render::camera::OrthographicProjection,
-            &mut Transform,
-        ),
-        Without<Player>,
+        (&mut Camera, &mut OrthographicProjection, &mut Transform),
+        (With<GameCamera>, Without<Player>),
     >,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    display_config: Res<DisplayConfig>,
     player_query: Query<&Transform, With<Player>>,
     level_query: Query<(&Transform, &LevelIid), (Without<OrthographicProjection>, Without<Player>)>,
     ldtk_projects: Query<&LdtkProjectHandle>,
     level_selection: Res<LevelSelection>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
 ) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
     if let Ok(Transform {
         translation: player_translation,
         ..
@@ -27,7 +94,12 @@ pub fn camera_fit_inside_current_level(
     {
         let player_translation = *player_translation;
 
-        let (mut orthographic_projection, mut camera_transform) = camera_query.single_mut();
+        // Only the game view follows the player, not the camera of the settings UI
+        let Ok((mut camera, mut orthographic_projection, mut camera_transform)) =
+            camera_query.get_single_mut()
+        else {
+            return;
+        };
 
         for (level_transform, level_iid) in &level_query {
             let ldtk_project = ldtk_project_assets
@@ -39,33 +111,78 @@ pub fn camera_fit_inside_current_level(
                 .expect("Spawned level should exist in LDtk project");
 
             if level_selection.is_match(&LevelIndices::default(), level) {
-                let level_ratio = level.px_wid as f32 / level.px_hei as f32;
+                let level_size = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+                let fit = fit_view(
+                    window.physical_size(),
+                    level_size,
+                    display_config.pixel_scaling,
+                );
+
+                camera.viewport = Some(Viewport {
+                    physical_position: fit.viewport_position,
+                    physical_size: fit.viewport_size,
+                    ..default()
+                });
                 orthographic_projection.viewport_origin = Vec2::ZERO;
-                if level_ratio > ASPECT_RATIO {
-                    // level is wider than the screen
-                    let height = (level.px_hei as f32 / 9.).round() * 9.;
-                    let width = height * ASPECT_RATIO;
-                    orthographic_projection.scaling_mode =
-                        bevy::render::camera::ScalingMode::Fixed { width, height };
-                    camera_transform.translation.x =
-                        (player_translation.x - level_transform.translation.x - width / 2.)
-                            .clamp(0., level.px_wid as f32 - width);
-                    camera_transform.translation.y = 0.;
-                } else {
-                    // level is taller than the screen
-                    let width = (level.px_wid as f32 / 16.).round() * 16.;
-                    let height = width / ASPECT_RATIO;
-                    orthographic_projection.scaling_mode =
-                        bevy::render::camera::ScalingMode::Fixed { width, height };
-                    camera_transform.translation.y =
-                        (player_translation.y - level_transform.translation.y - height / 2.)
-                            .clamp(0., level.px_hei as f32 - height);
-                    camera_transform.translation.x = 0.;
-                }
-
-                camera_transform.translation.x += level_transform.translation.x;
-                camera_transform.translation.y += level_transform.translation.y;
+                orthographic_projection.scaling_mode = ScalingMode::Fixed {
+                    width: fit.view_size.x,
+                    height: fit.view_size.y,
+                };
+
+                // Follow the player without showing anything outside of the level
+                let max_offset = (level_size - fit.view_size).max(Vec2::ZERO);
+                let offset = (player_translation.truncate()
+                    - level_transform.translation.truncate()
+                    - fit.view_size / 2.)
+                    .clamp(Vec2::ZERO, max_offset);
+
+                camera_transform.translation.x = level_transform.translation.x + offset.x;
+                camera_transform.translation.y = level_transform.translation.y + offset.y;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_follows_the_window_aspect_ratio() {
+        let level = Vec2::new(1024., 256.);
+
+        let fit = fit_view(UVec2::new(1080, 720), level, false);
+        assert_eq!(fit.viewport_position, UVec2::ZERO);
+        assert_eq!(fit.viewport_size, UVec2::new(1080, 720));
+        assert_eq!(fit.view_size, Vec2::new(384., 256.));
+
+        let fit = fit_view(UVec2::new(1920, 1080), level, false);
+        assert_eq!(fit.viewport_size, UVec2::new(1920, 1080));
+        assert!((fit.view_size.x / fit.view_size.y - 16. / 9.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn extreme_aspect_ratios_are_letterboxed() {
+        let level = Vec2::new(512., 512.);
+
+        // Ultra-wide: bars on the sides
+        let fit = fit_view(UVec2::new(3840, 1080), level, false);
+        assert_eq!(fit.viewport_size, UVec2::new(2520, 1080));
+        assert_eq!(fit.viewport_position, UVec2::new(660, 0));
+
+        // Portrait: bars above and below
+        let fit = fit_view(UVec2::new(600, 1000), level, false);
+        assert_eq!(fit.viewport_size, UVec2::new(600, 450));
+        assert_eq!(fit.viewport_position, UVec2::new(0, 275));
+    }
+
+    #[test]
+    fn pixel_scaling_magnifies_by_whole_factors() {
+        let level = Vec2::new(400., 240.);
+
+        let fit = fit_view(UVec2::new(1280, 720), level, true);
+        let scale = fit.viewport_size.as_vec2() / fit.view_size;
+        assert_eq!(scale, Vec2::splat(4.));
+        assert!(fit.view_size.x <= level.x && fit.view_size.y <= level.y);
+    }
+}
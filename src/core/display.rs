@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode};
+
+use crate::config::{DisplayConfig, WindowModeSetting};
+
+/// Window sizes offered in the options
+pub const RESOLUTION_PRESETS: [UVec2; 5] = [
+    UVec2::new(1080, 720),
+    UVec2::new(1280, 720),
+    UVec2::new(1600, 900),
+    UVec2::new(1920, 1080),
+    UVec2::new(2560, 1440),
+];
+
+impl From<WindowModeSetting> for WindowMode {
+    fn from(mode: WindowModeSetting) -> Self {
+        match mode {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen(MonitorSelection::Current),
+        }
+    }
+}
+
+/// Système qui applique les options d'affichage à la fenêtre principale
+pub fn apply_display_config(
+    display_config: Res<DisplayConfig>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !display_config.is_changed() {
+        return;
+    }
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    window.mode = display_config.window_mode.into();
+    if display_config.window_mode == WindowModeSetting::Windowed {
+        window.resolution.set(
+            display_config.resolution.x as f32,
+            display_config.resolution.y as f32,
+        );
+    }
+    window.present_mode = if display_config.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+}
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_display_config);
+    }
+}
@@ -1,32 +1,89 @@
-use crate::{entities::player::Player, GameState};
+use crate::core::camera::{GameCamera, LetterboxCamera};
+use crate::entities::movement::{MovementProfile, MovementProfileHandle};
+use crate::{entities::player::Player, GameState, InGameplay};
 use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+/// LDtk project loaded when a game session starts (path relative to the `assets` folder)
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct WorldMap(pub &'static str);
+
+impl Default for WorldMap {
+    fn default() -> Self {
+        Self("the_seventh_map.ldtk")
+    }
+}
+
+/// Sent when the player leaves the loaded levels anywhere but through the top
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FellOutOfLevelEvent {
+    pub entity: Entity,
+    /// Level the player fell out of
+    pub level: Entity,
+}
+
+/// Every entity spawned here is despawned, with its children, when the game session ends:
+/// the next session starts from a fresh world.
 pub fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    world_map: Res<WorldMap>,
+    movement_profile: Res<MovementProfileHandle>,
+    profiles: Res<Assets<MovementProfile>>,
     mut rapier_config: Query<&mut RapierConfiguration>,
 ) {
-    commands.spawn(Camera2d);
+    commands.spawn((Camera2d, GameCamera, StateScoped(InGameplay)));
+    // Clears the whole window behind the letterboxed game view
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        RenderLayers::none(),
+        LetterboxCamera,
+        StateScoped(InGameplay),
+    ));
 
-    rapier_config.single_mut().gravity = Vec2::new(0.0, -2000.0);
+    rapier_config.single_mut().gravity =
+        Vec2::new(0.0, -movement_profile.profile(&profiles).gravity);
+
+    // The levels and the worldly entities (the player) are children of the world
+    let ldtk_handle = asset_server.load(world_map.0).into();
+    commands.spawn((
+        LdtkWorldBundle {
+            ldtk_handle,
+            ..Default::default()
+        },
+        StateScoped(InGameplay),
+    ));
+}
 
-    let ldtk_handle = asset_server.load("the_seventh_map.ldtk").into();
-    commands.spawn(LdtkWorldBundle {
-        ldtk_handle,
-        ..Default::default()
-    });
+/// A new session starts in the first level, unless a checkpoint is loaded
+fn reset_level_selection(mut level_selection: ResMut<LevelSelection>) {
+    *level_selection = LevelSelection::default();
+}
+
+/// Système qui relance une session neuve une fois la précédente démontée
+fn restart_session(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::InGame);
 }
 
 pub fn update_level_selection(
-    level_query: Query<(&LevelIid, &Transform), Without<Player>>,
-    player_query: Query<&Transform, With<Player>>,
+    level_query: Query<(Entity, &LevelIid, &Transform), Without<Player>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
     mut level_selection: ResMut<LevelSelection>,
     ldtk_projects: Query<&LdtkProjectHandle>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
+    mut fell_events: EventWriter<FellOutOfLevelEvent>,
 ) {
-    for (level_iid, level_transform) in &level_query {
+    let mut current_level = None;
+    let mut players_in_a_level = Vec::new();
+
+    for (level_entity, level_iid, level_transform) in &level_query {
         let ldtk_project = ldtk_project_assets
             .get(ldtk_projects.single())
             .expect("Project should be loaded if level has spawned");
@@ -43,7 +100,16 @@ pub fn update_level_selection(
             ),
         };
 
-        for player_transform in &player_query {
+        if level_selection.is_match(&LevelIndices::default(), level) {
+            current_level = Some((level_entity, level_bounds));
+        }
+
+        for (player, player_transform) in &player_query {
+            // Standing right on the border of two levels is not falling out
+            if level_bounds.contains(player_transform.translation.truncate()) {
+                players_in_a_level.push(player);
+            }
+
             if player_transform.translation.x < level_bounds.max.x
                 && player_transform.translation.x > level_bounds.min.x
                 && player_transform.translation.y < level_bounds.max.y
@@ -54,14 +120,97 @@ pub fn update_level_selection(
             }
         }
     }
+
+    // Kill-plane : sorti de tous les niveaux chargés, par le bas ou par les côtés
+    let Some((level, bounds)) = current_level else {
+        return;
+    };
+    for (player, player_transform) in &player_query {
+        if !players_in_a_level.contains(&player) && player_transform.translation.y < bounds.max.y {
+            fell_events.send(FellOutOfLevelEvent {
+                entity: player,
+                level,
+            });
+        }
+    }
 }
 
 pub struct GameFlowPlugin;
 
 impl Plugin for GameFlowPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_level_selection)
-            .add_systems(OnEnter(GameState::InGame), setup)
-            .add_systems(OnEnter(GameState::InGame), update_level_selection);
+        app.add_event::<FellOutOfLevelEvent>()
+            .init_resource::<WorldMap>()
+            .init_resource::<LevelSelection>()
+            .add_systems(Update, update_level_selection)
+            .add_systems(OnEnter(InGameplay), setup)
+            .add_systems(OnEnter(InGameplay), update_level_selection)
+            .add_systems(OnExit(InGameplay), reset_level_selection)
+            .add_systems(OnEnter(GameState::Restarting), restart_session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_ecs_ldtk::prelude::*;
+
+    use crate::core::camera::LetterboxCamera;
+    use crate::testing::TestGame;
+    use crate::world::objects::WorldObjectStates;
+    use crate::GameState;
+
+    fn count<C: Component>(game: &mut TestGame) -> usize {
+        game.app
+            .world_mut()
+            .query_filtered::<(), With<C>>()
+            .iter(game.app.world())
+            .count()
+    }
+
+    #[test]
+    fn restarting_spawns_an_identical_fresh_world() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let spawn_position = game.position(player);
+        let entities = game.app.world().entities().len();
+
+        for _ in 0..3 {
+            // Leave a trace of the previous session
+            game.press_key(KeyCode::KeyD);
+            game.step(20);
+            game.release_key(KeyCode::KeyD);
+            game.app
+                .world_mut()
+                .resource_mut::<WorldObjectStates>()
+                .opened_chests
+                .insert("chest".to_string());
+
+            game.set_state(GameState::GameOver);
+            game.step(2);
+            assert_eq!(count::<LdtkProjectHandle>(&mut game), 0);
+            assert_eq!(count::<LetterboxCamera>(&mut game), 0);
+            assert!(game.try_player().is_none());
+
+            // "Recommencer" on the game over screen
+            game.tap_key(KeyCode::Enter);
+            game.step(1);
+            assert_eq!(game.state(), GameState::InGame);
+            game.start_session();
+            game.settle();
+
+            assert_eq!(count::<LdtkProjectHandle>(&mut game), 1);
+            assert_eq!(count::<Camera2d>(&mut game), 2);
+            let player = game.player();
+            assert_eq!(game.position(player), spawn_position);
+            assert!(game
+                .app
+                .world()
+                .resource::<WorldObjectStates>()
+                .opened_chests
+                .is_empty());
+            assert_eq!(game.app.world().entities().len(), entities);
+        }
     }
 }
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::core::save::{PendingCheckpoint, SaveSlots};
 use crate::input::{is_button_just_pressed, is_dpad_down_just_pressed, is_dpad_up_just_pressed, GamepadState};
 use crate::GameState;
 
@@ -51,6 +52,9 @@ fn setup_game_over_screen(mut commands: Commands, mut selected: ResMut<SelectedG
     // Reset selection
     selected.index = 0;
 
+    // The game cameras are despawned with the world
+    commands.spawn((Camera2d, GameOverUI));
+
     // Container principal
     commands
         .spawn((
@@ -216,12 +220,15 @@ fn update_game_over_button_visuals(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_game_over_buttons(
     mut next_state: ResMut<NextState<GameState>>,
     input: Res<ButtonInput<KeyCode>>,
     gamepad_state: Res<GamepadState>,
     gamepads: Query<&Gamepad>,
     selected: Res<SelectedGameOverButton>,
+    save_slots: Res<SaveSlots>,
+    mut pending_checkpoint: ResMut<PendingCheckpoint>,
     restart_query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
     menu_query: Query<&Interaction, (Changed<Interaction>, With<MenuButton>)>,
     mut button_colors: Query<(&Interaction, &mut BackgroundColor, &GameOverButtonIndex), With<Button>>,
@@ -256,6 +263,7 @@ fn handle_game_over_buttons(
         match selected.index {
             0 => {
                 info!("Recommencer le jeu");
+                pending_checkpoint.0 = save_slots.active_save().cloned();
                 next_state.set(GameState::InGame);
             }
             1 => {
@@ -271,6 +279,7 @@ fn handle_game_over_buttons(
     for interaction in restart_query.iter() {
         if *interaction == Interaction::Pressed {
             info!("Recommencer le jeu");
+            pending_checkpoint.0 = save_slots.active_save().cloned();
             next_state.set(GameState::InGame);
         }
     }
@@ -1,17 +1,242 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Component, Debug, Eq, Default, PartialEq)]
-pub struct Inventory(Vec<String>);
+/// Items of the game, mirroring the LDtk `Item` enum
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Item {
+    Knife,
+    #[serde(rename = "Healing_Plant")]
+    HealingPlant,
+    Meat,
+    Boots,
+    Water,
+    Gem,
+}
+
+impl Item {
+    pub const ALL: [Item; 6] = [
+        Item::Knife,
+        Item::HealingPlant,
+        Item::Meat,
+        Item::Boots,
+        Item::Water,
+        Item::Gem,
+    ];
+
+    /// Identifier of the value in the LDtk `Item` enum
+    pub fn ldtk_name(&self) -> &'static str {
+        match self {
+            Item::Knife => "Knife",
+            Item::HealingPlant => "Healing_Plant",
+            Item::Meat => "Meat",
+            Item::Boots => "Boots",
+            Item::Water => "Water",
+            Item::Gem => "Gem",
+        }
+    }
+
+    pub fn from_ldtk_name(name: &str) -> Option<Item> {
+        Item::ALL.into_iter().find(|item| item.ldtk_name() == name)
+    }
+
+    /// Nom affiché au joueur
+    pub fn label(&self) -> &'static str {
+        match self {
+            Item::Knife => "Couteau",
+            Item::HealingPlant => "Plante de soin",
+            Item::Meat => "Viande",
+            Item::Boots => "Bottes",
+            Item::Water => "Eau",
+            Item::Gem => "Gemme",
+        }
+    }
+}
+
+/// Parse the values of an LDtk `Array<LocalEnum.Item>` field, skipping the unknown ones
+pub fn items_from_field(entity_instance: &EntityInstance, field: &str) -> Vec<Item> {
+    entity_instance
+        .iter_enums_field(field)
+        .unwrap_or_else(|_| panic!("{} field should be correctly typed", field))
+        .filter_map(|name| {
+            let item = Item::from_ldtk_name(name);
+            if item.is_none() {
+                warn!("Unknown item {} in field {}", name, field);
+            }
+            item
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: Item,
+    pub count: u32,
+}
+
+/// Inventaire du joueur : une pile par type d'objet, dans l'ordre de ramassage
+#[derive(Clone, Component, Debug, Eq, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Item, count: u32) {
+        if count == 0 {
+            return;
+        }
+
+        match self.stacks.iter_mut().find(|stack| stack.item == item) {
+            Some(stack) => stack.count += count,
+            None => self.stacks.push(ItemStack { item, count }),
+        }
+    }
+
+    /// Remove `count` items, or nothing at all if there are not enough of them
+    pub fn remove(&mut self, item: Item, count: u32) -> bool {
+        let Some(index) = self.stacks.iter().position(|stack| stack.item == item) else {
+            return false;
+        };
+
+        let stack = &mut self.stacks[index];
+        if stack.count < count {
+            return false;
+        }
+
+        stack.count -= count;
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        true
+    }
+
+    pub fn has(&self, item: Item) -> bool {
+        self.count(item) > 0
+    }
+
+    pub fn count(&self, item: Item) -> u32 {
+        self.stacks
+            .iter()
+            .find(|stack| stack.item == item)
+            .map_or(0, |stack| stack.count)
+    }
+
+    pub fn stacks(&self) -> &[ItemStack] {
+        &self.stacks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+}
+
+impl FromIterator<Item> for Inventory {
+    fn from_iter<I: IntoIterator<Item = Item>>(iter: I) -> Self {
+        let mut inventory = Inventory::default();
+        for item in iter {
+            inventory.add(item, 1);
+        }
+        inventory
+    }
+}
 
 impl From<&EntityInstance> for Inventory {
     fn from(entity_instance: &EntityInstance) -> Self {
-        Inventory(
-            entity_instance
-                .iter_enums_field("items")
-                .expect("items field should be correctly typed")
-                .cloned()
-                .collect(),
-        )
+        items_from_field(entity_instance, "items")
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Envoyé quand une entité ramasse un objet
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ItemPickedUp {
+    pub entity: Entity,
+    pub item: Item,
+    pub count: u32,
+}
+
+/// Envoyé quand une entité utilise un objet de son inventaire
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ItemUsed {
+    pub entity: Entity,
+    pub item: Item,
+}
+
+/// Système qui ajoute les objets ramassés à l'inventaire de l'entité
+fn add_picked_up_items(
+    mut picked_up_events: EventReader<ItemPickedUp>,
+    mut inventory_query: Query<&mut Inventory>,
+) {
+    for event in picked_up_events.read() {
+        if let Ok(mut inventory) = inventory_query.get_mut(event.entity) {
+            inventory.add(event.item, event.count);
+            info!("Objet ramassé : {} x{}", event.item.label(), event.count);
+        }
+    }
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ItemPickedUp>()
+            .add_event::<ItemUsed>()
+            .add_systems(Update, add_picked_up_items);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_of_the_same_type_are_stacked_in_pickup_order() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Meat, 1);
+        inventory.add(Item::Knife, 1);
+        inventory.add(Item::Meat, 2);
+        inventory.add(Item::Gem, 0);
+
+        assert_eq!(
+            inventory.stacks(),
+            [
+                ItemStack {
+                    item: Item::Meat,
+                    count: 3
+                },
+                ItemStack {
+                    item: Item::Knife,
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(inventory.count(Item::Meat), 3);
+        assert_eq!(inventory.count(Item::Gem), 0);
+    }
+
+    #[test]
+    fn removing_more_items_than_owned_changes_nothing() {
+        let mut inventory: Inventory = [Item::Water, Item::Water].into_iter().collect();
+
+        assert!(!inventory.remove(Item::Water, 3));
+        assert!(!inventory.remove(Item::Boots, 1));
+        assert_eq!(inventory.count(Item::Water), 2);
+
+        assert!(inventory.remove(Item::Water, 1));
+        assert_eq!(inventory.count(Item::Water), 1);
+    }
+
+    #[test]
+    fn emptied_stacks_are_removed() {
+        let mut inventory: Inventory = [Item::Boots, Item::Knife].into_iter().collect();
+        assert!(inventory.has(Item::Boots));
+
+        assert!(inventory.remove(Item::Boots, 1));
+        assert!(!inventory.has(Item::Boots));
+        assert_eq!(inventory.stacks().len(), 1);
+
+        assert!(inventory.remove(Item::Knife, 1));
+        assert!(inventory.is_empty());
     }
 }
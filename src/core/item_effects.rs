@@ -0,0 +1,172 @@
+//! Data-driven item effects.
+//!
+//! What each item does is described in `assets/data/items.effects.json`, so the effects
+//! can be tuned without touching the systems. Consumable items apply their effects once
+//! when used from the inventory; the effects of the other items apply as long as the
+//! player carries them.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use crate::engine::json_asset::JsonAssetAppExt;
+use crate::entities::player::Player;
+use crate::entities::stats::Stats;
+
+use super::inventory::{Inventory, Item, ItemUsed};
+
+/// Path of the item effect table in the assets
+const ITEM_EFFECTS_PATH: &str = "data/items.effects.json";
+
+/// Effect of an item, as written in the effect table
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemEffect {
+    /// Restore life points, up to the maximum life
+    Heal(i32),
+    /// Multiply the walking speed of the player
+    MoveSpeedMultiplier(f32),
+    /// Jumps allowed while in the air
+    ExtraJumps(u32),
+    /// Added to the damage of the player's attacks
+    AttackBonus(i32),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ItemDefinition {
+    /// Consumable items are removed from the inventory when used
+    #[serde(default)]
+    pub consumable: bool,
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+}
+
+/// Table of the item effects, loaded from the assets
+#[derive(Asset, TypePath, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct ItemEffectTable(pub HashMap<Item, ItemDefinition>);
+
+/// Resource holding the handle of the item effect table
+#[derive(Resource, Default)]
+pub struct ItemEffects {
+    pub table: Handle<ItemEffectTable>,
+}
+
+impl ItemEffects {
+    /// Definition of an item, `None` while the table is loading or if the item is missing
+    pub fn definition<'a>(
+        &self,
+        tables: &'a Assets<ItemEffectTable>,
+        item: Item,
+    ) -> Option<&'a ItemDefinition> {
+        tables.get(&self.table)?.0.get(&item)
+    }
+
+    pub fn is_consumable(&self, tables: &Assets<ItemEffectTable>, item: Item) -> bool {
+        self.definition(tables, item)
+            .is_some_and(|definition| definition.consumable)
+    }
+}
+
+/// Modificateurs permanents du joueur, donnés par les objets non consommables qu'il porte
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerModifiers {
+    pub move_speed_multiplier: f32,
+    pub extra_jumps: u32,
+    pub attack_bonus: i32,
+}
+
+impl Default for PlayerModifiers {
+    fn default() -> Self {
+        Self {
+            move_speed_multiplier: 1.0,
+            extra_jumps: 0,
+            attack_bonus: 0,
+        }
+    }
+}
+
+impl PlayerModifiers {
+    fn apply(&mut self, effect: ItemEffect) {
+        match effect {
+            ItemEffect::MoveSpeedMultiplier(multiplier) => self.move_speed_multiplier *= multiplier,
+            ItemEffect::ExtraJumps(jumps) => self.extra_jumps += jumps,
+            ItemEffect::AttackBonus(bonus) => self.attack_bonus += bonus,
+            // Only applied when the item is used
+            ItemEffect::Heal(_) => {}
+        }
+    }
+}
+
+fn load_item_effects(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ItemEffects {
+        table: asset_server.load(ITEM_EFFECTS_PATH),
+    });
+}
+
+/// Système qui applique les effets des objets consommés
+fn apply_used_item_effects(
+    mut item_used_events: EventReader<ItemUsed>,
+    item_effects: Res<ItemEffects>,
+    tables: Res<Assets<ItemEffectTable>>,
+    mut stats_query: Query<&mut Stats>,
+) {
+    for event in item_used_events.read() {
+        let Some(definition) = item_effects.definition(&tables, event.item) else {
+            continue;
+        };
+        let Ok(mut stats) = stats_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        for effect in &definition.effects {
+            if let ItemEffect::Heal(amount) = effect {
+                stats.life = (stats.life + amount).min(stats.max_life);
+                info!("Soin de {} : {}/{}", amount, stats.life, stats.max_life);
+            }
+        }
+    }
+}
+
+/// Système qui recalcule les modificateurs du joueur à partir de son inventaire
+fn update_player_modifiers(
+    mut commands: Commands,
+    item_effects: Res<ItemEffects>,
+    tables: Res<Assets<ItemEffectTable>>,
+    mut player_query: Query<(Entity, &Inventory, Option<&mut PlayerModifiers>), With<Player>>,
+) {
+    let table = tables.get(&item_effects.table);
+
+    for (entity, inventory, current_modifiers) in player_query.iter_mut() {
+        let mut modifiers = PlayerModifiers::default();
+        // Un objet permanent agit une seule fois, quel que soit le nombre porté
+        for (item, definition) in table.iter().flat_map(|table| &table.0) {
+            if !definition.consumable && inventory.has(*item) {
+                definition
+                    .effects
+                    .iter()
+                    .for_each(|effect| modifiers.apply(*effect));
+            }
+        }
+
+        match current_modifiers {
+            Some(mut current_modifiers) => {
+                current_modifiers.set_if_neq(modifiers);
+            }
+            None => {
+                commands.entity(entity).insert(modifiers);
+            }
+        }
+    }
+}
+
+pub struct ItemEffectsPlugin;
+
+impl Plugin for ItemEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_json_asset::<ItemEffectTable>(&["effects.json"])
+            .init_resource::<ItemEffects>()
+            .add_systems(Startup, load_item_effects)
+            .add_systems(Update, (apply_used_item_effects, update_player_modifiers));
+    }
+}
@@ -1,8 +1,14 @@
 /// Handles camera positioning and scaling
 pub mod camera;
+/// Applies the display options to the window
+pub mod display;
 /// Handles initialization and switching levels
 pub mod game_flow;
 /// Handles the game over screen
 pub mod game_over;
 /// Inventory system
 pub mod inventory;
+/// Data-driven item effects
+pub mod item_effects;
+/// Save slots and checkpoints
+pub mod save;
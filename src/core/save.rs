@@ -0,0 +1,332 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::dynamics::Velocity;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{write_atomically, GameConfig};
+use crate::core::inventory::{Inventory, Item};
+use crate::entities::player::Player;
+use crate::entities::stats::Stats;
+use crate::gui::dialog::ShowTopDialog;
+use crate::world::interaction::InteractEvent;
+use crate::world::objects::SavePoint;
+use crate::{GameState, InGameplay};
+
+/// Version of the save file format, bumped whenever `SaveData` changes
+pub const SAVE_VERSION: u32 = 2;
+
+/// Number of save slots available to the player
+pub const SAVE_SLOT_COUNT: usize = 3;
+
+/// Content of a save file, written when the player uses a save point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    /// Unix timestamp (in seconds) of the save
+    pub saved_at: u64,
+    /// Iid of the level holding the save point
+    pub level_iid: String,
+    /// World position of the player when saving
+    pub position: [f32; 2],
+    pub stats: Stats,
+    pub inventory: Inventory,
+}
+
+impl SaveData {
+    /// Get the save file path for the given slot
+    fn slot_path(slot: usize) -> PathBuf {
+        GameConfig::data_path(&format!("save_{}.json", slot + 1))
+    }
+
+    /// Load the save of the given slot, `Ok(None)` if the slot is empty
+    pub fn load(slot: usize) -> Result<Option<Self>, String> {
+        let path = Self::slot_path(slot);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read save file: {}", e))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    /// Parse the content of a save file, upgrading it to the current format
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| format!("Failed to parse save file: {}", e))?;
+        serde_json::from_value::<SaveData>(Self::migrate(value)?)
+            .map_err(|e| format!("Failed to parse save file: {}", e))
+    }
+
+    /// Upgrade a save written by an older version of the game to the current format
+    fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0) as u32;
+
+        if version > SAVE_VERSION {
+            return Err(format!(
+                "Save file version {} is newer than the supported version {}",
+                version, SAVE_VERSION
+            ));
+        }
+
+        // v1 stored the inventory as a list of LDtk item names
+        if version < 2 {
+            let inventory: Inventory = value
+                .get("inventory")
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(serde_json::Value::as_str)
+                .filter_map(Item::from_ldtk_name)
+                .collect();
+            value["inventory"] = serde_json::to_value(inventory)
+                .map_err(|e| format!("Failed to migrate save file: {}", e))?;
+        }
+
+        value["version"] = SAVE_VERSION.into();
+        Ok(value)
+    }
+
+    /// Write the save to the given slot
+    pub fn save(&self, slot: usize) -> Result<(), String> {
+        let path = Self::slot_path(slot);
+        match serde_json::to_string_pretty(self) {
+            Ok(content) => match write_atomically(&path, &content) {
+                Ok(_) => {
+                    info!("Game saved to {:?}", path);
+                    Ok(())
+                }
+                Err(e) => Err(format!("Failed to write save file: {}", e)),
+            },
+            Err(e) => Err(format!("Failed to serialize save: {}", e)),
+        }
+    }
+}
+
+/// Resource holding the content of every save slot and the one currently in use
+#[derive(Resource, Debug, Default)]
+pub struct SaveSlots {
+    pub active: usize,
+    pub saves: Vec<Option<SaveData>>,
+}
+
+impl SaveSlots {
+    /// Load every save slot from disk, selecting the most recent save as active slot
+    pub fn load() -> Self {
+        let saves: Vec<Option<SaveData>> = (0..SAVE_SLOT_COUNT)
+            .map(|slot| {
+                SaveData::load(slot).unwrap_or_else(|e| {
+                    warn!("Save slot {}: {}", slot + 1, e);
+                    None
+                })
+            })
+            .collect();
+
+        let active = saves
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, save)| save.as_ref().map(|save| (slot, save.saved_at)))
+            .max_by_key(|(_, saved_at)| *saved_at)
+            .map(|(slot, _)| slot)
+            .unwrap_or(0);
+
+        Self { active, saves }
+    }
+
+    /// Save of the active slot, if any
+    pub fn active_save(&self) -> Option<&SaveData> {
+        self.saves.get(self.active).and_then(Option::as_ref)
+    }
+
+    /// Select the next slot (wrapping around)
+    pub fn select_next(&mut self) {
+        self.active = (self.active + 1) % SAVE_SLOT_COUNT;
+    }
+
+    /// Write a save to the active slot
+    pub fn write_active(&mut self, data: SaveData) -> Result<(), String> {
+        data.save(self.active)?;
+        if let Some(slot) = self.saves.get_mut(self.active) {
+            *slot = Some(data);
+        }
+        Ok(())
+    }
+}
+
+/// Checkpoint to restore once the player is spawned
+#[derive(Resource, Default)]
+pub struct PendingCheckpoint(pub Option<SaveData>);
+
+/// Système qui sauvegarde la partie quand le joueur interagit avec un point de sauvegarde
+fn save_at_save_point(
+    mut interact_events: EventReader<InteractEvent>,
+    save_points: Query<&Parent, With<SavePoint>>,
+    parent_query: Query<&Parent, Without<SavePoint>>,
+    level_query: Query<&LevelIid>,
+    player_query: Query<(&Stats, &Inventory, &Transform), With<Player>>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut dialog_events: EventWriter<ShowTopDialog>,
+) {
+    for event in interact_events.read() {
+        let Ok(layer) = save_points.get(event.target) else {
+            continue;
+        };
+        let Ok((stats, inventory, transform)) = player_query.get(event.player) else {
+            continue;
+        };
+
+        // A save point's direct parent is the layer entity, the level is its grandparent
+        let Some(level_iid) = parent_query
+            .get(layer.get())
+            .ok()
+            .and_then(|level| level_query.get(level.get()).ok())
+        else {
+            warn!("Save point {:?} is not part of a level", event.target);
+            continue;
+        };
+
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let data = SaveData {
+            version: SAVE_VERSION,
+            saved_at,
+            level_iid: level_iid.to_string(),
+            position: [transform.translation.x, transform.translation.y],
+            stats: stats.clone(),
+            inventory: inventory.clone(),
+        };
+
+        let text = match save_slots.write_active(data) {
+            Ok(_) => format!("Partie sauvegardée (emplacement {})", save_slots.active + 1),
+            Err(e) => {
+                warn!("Failed to save game: {}", e);
+                "La sauvegarde a échoué".to_string()
+            }
+        };
+
+        dialog_events.send(ShowTopDialog {
+            text,
+            speaker: None,
+        });
+    }
+}
+
+/// Système qui replace le joueur au dernier point de sauvegarde chargé
+pub fn apply_pending_checkpoint(
+    mut pending: ResMut<PendingCheckpoint>,
+    mut player_query: Query<
+        (&mut Stats, &mut Inventory, &mut Transform, &mut Velocity),
+        With<Player>,
+    >,
+    adopted_players: Query<&Parent, With<Player>>,
+    worlds: Query<(), With<LdtkProjectHandle>>,
+    mut level_selection: ResMut<LevelSelection>,
+) {
+    if pending.0.is_none() || player_query.is_empty() {
+        return;
+    }
+    // A player that just spawned is placed relative to its layer until the world adopts it
+    if adopted_players
+        .iter()
+        .any(|parent| !worlds.contains(parent.get()))
+    {
+        return;
+    }
+
+    let Some(save) = pending.0.take() else {
+        return;
+    };
+
+    for (mut stats, mut inventory, mut transform, mut velocity) in player_query.iter_mut() {
+        *stats = save.stats.clone();
+        *inventory = save.inventory.clone();
+        transform.translation.x = save.position[0];
+        transform.translation.y = save.position[1];
+        velocity.linvel = Vec2::ZERO;
+    }
+
+    *level_selection = LevelSelection::iid(save.level_iid);
+    info!("Partie reprise depuis le dernier point de sauvegarde");
+}
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveSlots::load())
+            .init_resource::<PendingCheckpoint>()
+            .add_systems(OnEnter(InGameplay), apply_pending_checkpoint)
+            .add_systems(
+                Update,
+                (save_at_save_point, apply_pending_checkpoint)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_data() -> SaveData {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Meat, 2);
+        inventory.add(Item::Knife, 1);
+        SaveData {
+            version: SAVE_VERSION,
+            saved_at: 1_700_000_000,
+            level_iid: "c0ffee00-0000-11f1-a000-000000000010".to_string(),
+            position: [72.0, -197.5],
+            stats: Stats {
+                life: 7,
+                max_life: 10,
+                damage: 2,
+            },
+            inventory,
+        }
+    }
+
+    #[test]
+    fn save_round_trips_through_json() {
+        let data = save_data();
+        let json = serde_json::to_string_pretty(&data).unwrap();
+
+        assert_eq!(SaveData::from_json(&json).unwrap(), data);
+    }
+
+    #[test]
+    fn v1_inventory_of_ldtk_names_is_migrated_to_stacks() {
+        let json = r#"{
+            "version": 1,
+            "saved_at": 1700000000,
+            "level_iid": "c0ffee00-0000-11f1-a000-000000000010",
+            "position": [72.0, -197.5],
+            "stats": { "life": 7, "max_life": 10, "damage": 2 },
+            "inventory": ["Meat", "Knife", "Meat", "Unknown_Item"]
+        }"#;
+
+        let data = SaveData::from_json(json).unwrap();
+
+        assert_eq!(data, save_data());
+    }
+
+    #[test]
+    fn save_from_a_newer_version_is_rejected() {
+        let mut value = serde_json::to_value(save_data()).unwrap();
+        value["version"] = (SAVE_VERSION + 1).into();
+
+        let error = SaveData::from_json(&value.to_string()).unwrap_err();
+
+        assert!(error.contains("newer"), "{error}");
+    }
+}
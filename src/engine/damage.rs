@@ -1,9 +1,15 @@
-use std::time::Duration;
+//! Pipeline unique de santé et de dégâts.
+//!
+//! Every hit goes through a [`DamageEvent`]: the stats are updated once, the target gets
+//! its own invincibility frames, a [`DamageApplied`] reports the hits that were not
+//! absorbed, and a single [`DeathEvent`] is sent when it dies.
 
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 use bevy_rapier2d::prelude::*;
 
 use crate::entities::enemy::Enemy;
+use crate::entities::movement::Movement;
 use crate::entities::player::Player;
 use crate::entities::stats::Stats;
 use crate::GameState;
@@ -16,16 +22,6 @@ pub struct Invincibility {
     pub visible: bool,
 }
 
-impl Default for Invincibility {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(1.5, TimerMode::Once),
-            blink_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            visible: true,
-        }
-    }
-}
-
 impl Invincibility {
     pub fn new(duration: f32) -> Self {
         Self {
@@ -36,6 +32,22 @@ impl Invincibility {
     }
 }
 
+/// Durée de l'invincibilité (en secondes) donnée à une entité après un coup
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct InvincibilityDuration(pub f32);
+
+impl InvincibilityDuration {
+    pub const PLAYER: Self = Self(3.0);
+    pub const ENEMY: Self = Self(0.5);
+}
+
+/// Entities without their own duration get the enemy one
+impl Default for InvincibilityDuration {
+    fn default() -> Self {
+        Self::ENEMY
+    }
+}
+
 /// Composant pour gérer le recul (knockback)
 #[derive(Component)]
 pub struct Knockback {
@@ -80,8 +92,14 @@ impl DamageEvent {
     }
 }
 
-/// Événement déclenché quand une entité meurt
-#[derive(Event)]
+/// Événement envoyé quand des dégâts sont réellement appliqués (hors invincibilité)
+#[derive(Event, Debug, Clone)]
+pub struct DamageApplied {
+    pub entity: Entity,
+}
+
+/// Événement déclenché une seule fois quand une entité meurt
+#[derive(Event, Debug, Clone)]
 pub struct DeathEvent {
     pub entity: Entity,
     pub is_player: bool,
@@ -107,6 +125,7 @@ fn handle_player_enemy_collisions(
     player_query: Query<Entity, (With<Player>, Without<Invincibility>)>,
     enemy_query: Query<(Entity, &Stats, &Transform), With<Enemy>>,
     player_transform_query: Query<&Transform, With<Player>>,
+    movement_query: Query<&Movement>,
     mut damage_events: EventWriter<DamageEvent>,
 ) {
     for collision_event in collision_events.read() {
@@ -140,10 +159,16 @@ fn handle_player_enemy_collisions(
                     Vec2::X
                 };
 
+            let knockback_force = movement_query
+                .get(player_entity)
+                .map_or(Movement::default().hurt_knockback, |movement| {
+                    movement.hurt_knockback
+                });
+
             // Envoyer l'événement de dégâts avec knockback pour le joueur
             damage_events.send(
                 DamageEvent::new(player_entity, enemy_entity, enemy_stats.damage)
-                    .with_knockback(knockback_direction, 400.0),
+                    .with_knockback(knockback_direction, knockback_force),
             );
         }
     }
@@ -153,54 +178,65 @@ fn handle_player_enemy_collisions(
 fn apply_damage(
     mut commands: Commands,
     mut damage_events: EventReader<DamageEvent>,
-    mut stats_query: Query<&mut Stats>,
+    mut stats_query: Query<(&mut Stats, Option<&InvincibilityDuration>)>,
     invincibility_query: Query<&Invincibility>,
     player_query: Query<Entity, With<Player>>,
+    mut applied_events: EventWriter<DamageApplied>,
     mut death_events: EventWriter<DeathEvent>,
 ) {
+    // The invincibility is only inserted at the end of the frame: several hits on the
+    // same target in one frame (e.g. a slash touching two colliders) count as one
+    let mut hit_this_frame = HashSet::new();
+
     for event in damage_events.read() {
         // Ignorer si l'entité est invincible
-        if invincibility_query.get(event.target).is_ok() {
+        if invincibility_query.contains(event.target) || hit_this_frame.contains(&event.target) {
             continue;
         }
 
-        // Appliquer les dégâts aux stats
-        if let Ok(mut stats) = stats_query.get_mut(event.target) {
-            stats.life -= event.amount;
-
-            let is_player = player_query.get(event.target).is_ok();
-            let entity_type = if is_player { "Joueur" } else { "Ennemi" };
+        let Ok((mut stats, invincibility_duration)) = stats_query.get_mut(event.target) else {
+            continue;
+        };
 
-            info!(
-                "{} {:?} a pris {} dégâts - Vie: {}/{}",
-                entity_type, event.target, event.amount, stats.life, stats.max_life
-            );
+        // Already dead, waiting to be despawned
+        if stats.life <= 0 {
+            continue;
+        }
 
-            // Ajouter l'invincibilité temporaire
-            let invincibility_duration = if is_player { 3.0 } else { 0.5 };
+        hit_this_frame.insert(event.target);
+        stats.life -= event.amount;
+
+        let is_player = player_query.contains(event.target);
+        let entity_type = if is_player { "Joueur" } else { "Ennemi" };
+
+        info!(
+            "{} {:?} a pris {} dégâts de {:?} - Vie: {}/{}",
+            entity_type, event.target, event.amount, event.source, stats.life, stats.max_life
+        );
+        applied_events.send(DamageApplied {
+            entity: event.target,
+        });
+
+        // Ajouter l'invincibilité temporaire et le flash de dégâts
+        let invincibility_duration = invincibility_duration.copied().unwrap_or_default();
+        commands.entity(event.target).insert((
+            Invincibility::new(invincibility_duration.0),
+            DamageFlash::default(),
+        ));
+
+        // Ajouter le knockback si spécifié
+        if let (Some(direction), Some(force)) = (event.knockback_direction, event.knockback_force) {
             commands
                 .entity(event.target)
-                .insert(Invincibility::new(invincibility_duration));
-
-            // Ajouter le flash de dégâts
-            commands.entity(event.target).insert(DamageFlash::default());
-
-            // Ajouter le knockback si spécifié
-            if let (Some(direction), Some(force)) =
-                (event.knockback_direction, event.knockback_force)
-            {
-                commands
-                    .entity(event.target)
-                    .insert(Knockback::new(direction, force));
-            }
-
-            // Vérifier la mort
-            if stats.life <= 0 {
-                death_events.send(DeathEvent {
-                    entity: event.target,
-                    is_player,
-                });
-            }
+                .insert(Knockback::new(direction, force));
+        }
+
+        // Vérifier la mort
+        if stats.life <= 0 {
+            death_events.send(DeathEvent {
+                entity: event.target,
+                is_player,
+            });
         }
     }
 }
@@ -238,10 +274,10 @@ fn update_invincibility(
     }
 }
 
-/// Système qui fait clignoter les entités invincibles
+/// Système qui fait clignoter les entités invincibles (le flash de dégâts est prioritaire)
 fn update_invincibility_blink(
     time: Res<Time>,
-    mut query: Query<(&mut Invincibility, &mut Sprite)>,
+    mut query: Query<(&mut Invincibility, &mut Sprite), Without<DamageFlash>>,
 ) {
     for (mut invincibility, mut sprite) in query.iter_mut() {
         invincibility.blink_timer.tick(time.delta());
@@ -278,9 +314,14 @@ fn update_knockback(
 fn update_damage_flash(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut DamageFlash, &mut Sprite)>,
+    mut query: Query<(
+        Entity,
+        &mut DamageFlash,
+        &mut Sprite,
+        Option<&Invincibility>,
+    )>,
 ) {
-    for (entity, mut flash, mut sprite) in query.iter_mut() {
+    for (entity, mut flash, mut sprite, invincibility) in query.iter_mut() {
         // Appliquer la couleur rouge au début
         if flash.timer.elapsed_secs() == 0.0 {
             sprite.color = Color::srgb(1.0, 0.3, 0.3);
@@ -289,7 +330,11 @@ fn update_damage_flash(
         flash.timer.tick(time.delta());
 
         if flash.timer.finished() {
-            sprite.color = Color::WHITE;
+            // Reprendre le clignotement là où il en était
+            sprite.color = match invincibility {
+                Some(invincibility) if !invincibility.visible => Color::srgba(1.0, 1.0, 1.0, 0.3),
+                _ => Color::WHITE,
+            };
             commands.entity(entity).remove::<DamageFlash>();
         }
     }
@@ -300,6 +345,7 @@ pub struct DamagePlugin;
 impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<DamageEvent>()
+            .add_event::<DamageApplied>()
             .add_event::<DeathEvent>()
             .add_systems(
                 Update,
@@ -309,10 +355,178 @@ impl Plugin for DamagePlugin {
                     handle_deaths,
                     update_invincibility,
                     update_invincibility_blink,
-                    update_knockback,
                     update_damage_flash,
+                    update_knockback,
                 )
+                    .chain()
                     .run_if(in_state(GameState::InGame)),
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn damage_app() -> App {
+        let mut app = App::new();
+        app.add_event::<DamageEvent>()
+            .add_event::<DamageApplied>()
+            .add_event::<DeathEvent>()
+            .add_systems(Update, apply_damage);
+        app
+    }
+
+    fn spawn_target(app: &mut App, life: i32) -> Entity {
+        app.world_mut()
+            .spawn(Stats {
+                life,
+                max_life: life,
+                damage: 1,
+            })
+            .id()
+    }
+
+    fn life(app: &App, entity: Entity) -> i32 {
+        app.world().get::<Stats>(entity).unwrap().life
+    }
+
+    fn death_count(app: &App) -> usize {
+        let events = app.world().resource::<Events<DeathEvent>>();
+        events.get_cursor().read(events).count()
+    }
+
+    #[test]
+    fn hits_in_the_same_frame_are_applied_once() {
+        let mut app = damage_app();
+        let source = app.world_mut().spawn_empty().id();
+        let target = spawn_target(&mut app, 10);
+
+        for _ in 0..3 {
+            app.world_mut()
+                .send_event(DamageEvent::new(target, source, 2));
+        }
+        app.update();
+
+        assert_eq!(life(&app, target), 8);
+    }
+
+    #[test]
+    fn invincible_targets_ignore_hits() {
+        let mut app = damage_app();
+        let source = app.world_mut().spawn_empty().id();
+        let target = spawn_target(&mut app, 10);
+
+        app.world_mut()
+            .send_event(DamageEvent::new(target, source, 2));
+        app.update();
+        app.world_mut()
+            .send_event(DamageEvent::new(target, source, 2));
+        app.update();
+
+        assert_eq!(life(&app, target), 8);
+        assert!(app.world().get::<Invincibility>(target).is_some());
+    }
+
+    #[test]
+    fn invincibility_uses_the_target_duration() {
+        let mut app = damage_app();
+        let source = app.world_mut().spawn_empty().id();
+        let player = spawn_target(&mut app, 10);
+        app.world_mut()
+            .entity_mut(player)
+            .insert(InvincibilityDuration::PLAYER);
+        let enemy = spawn_target(&mut app, 10);
+
+        app.world_mut()
+            .send_event(DamageEvent::new(player, source, 1));
+        app.world_mut()
+            .send_event(DamageEvent::new(enemy, source, 1));
+        app.update();
+
+        let duration = |entity| {
+            app.world()
+                .get::<Invincibility>(entity)
+                .unwrap()
+                .timer
+                .duration()
+                .as_secs_f32()
+        };
+        assert_eq!(duration(player), InvincibilityDuration::PLAYER.0);
+        assert_eq!(duration(enemy), InvincibilityDuration::ENEMY.0);
+    }
+
+    #[test]
+    fn lethal_hits_send_a_single_death_event() {
+        let mut app = damage_app();
+        let source = app.world_mut().spawn_empty().id();
+        let target = spawn_target(&mut app, 2);
+        app.world_mut()
+            .entity_mut(target)
+            .insert(InvincibilityDuration(0.0));
+
+        app.world_mut()
+            .send_event(DamageEvent::new(target, source, 5));
+        app.world_mut()
+            .send_event(DamageEvent::new(target, source, 5));
+        app.update();
+        // No invincibility left, but the target is already dead
+        app.world_mut().entity_mut(target).remove::<Invincibility>();
+        app.world_mut()
+            .send_event(DamageEvent::new(target, source, 5));
+        app.update();
+
+        assert_eq!(life(&app, target), -3);
+        assert_eq!(death_count(&app), 1);
+    }
+
+    mod simulation {
+        use super::*;
+        use crate::testing::TestGame;
+
+        /// Walk the player of the fixture into the mob standing on its right
+        fn walk_into_mob(game: &mut TestGame) {
+            game.press_key(KeyCode::KeyD);
+            let player = game.player();
+            game.step_until(180, |game| game.get::<Invincibility>(player).is_some());
+            game.release_key(KeyCode::KeyD);
+        }
+
+        #[test]
+        fn touching_a_mob_hurts_the_player_once() {
+            let mut game = TestGame::new();
+            game.record::<DamageApplied>();
+            game.settle();
+            let player = game.player();
+            let life = game.stats(player).life;
+
+            walk_into_mob(&mut game);
+            game.step(30);
+
+            assert_eq!(game.stats(player).life, life - 1);
+            assert!(game.get::<Invincibility>(player).is_some());
+            assert_eq!(game.state(), GameState::InGame);
+            let applied = game.recorded::<DamageApplied>();
+            assert_eq!(applied.len(), 1);
+            assert_eq!(applied[0].entity, player);
+        }
+
+        #[test]
+        fn player_death_leads_to_game_over() {
+            let mut game = TestGame::new();
+            game.record::<DeathEvent>();
+            game.settle();
+            let player = game.player();
+            game.get_mut::<Stats>(player).life = 1;
+
+            walk_into_mob(&mut game);
+            game.step(2);
+
+            assert_eq!(game.state(), GameState::GameOver);
+            let deaths = game.recorded::<DeathEvent>();
+            assert_eq!(deaths.len(), 1);
+            assert_eq!(deaths[0].entity, player);
+            assert!(deaths[0].is_player);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Generic loader for data assets written in JSON (item effects, tuning tables, ...).
+
+use std::marker::PhantomData;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JsonAssetLoaderError {
+    #[error("could not read the asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse the asset: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Loads any deserializable asset from a JSON file.
+///
+/// Each asset type gets its own double extension (e.g. `effects.json`) so several
+/// loaders can live side by side with plain `.json` files.
+pub struct JsonAssetLoader<T> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonAssetLoader<T> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for JsonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = JsonAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+/// Helper to register a JSON asset type and its loader in one call
+pub trait JsonAssetAppExt {
+    fn register_json_asset<T: Asset + DeserializeOwned>(
+        &mut self,
+        extensions: &'static [&'static str],
+    ) -> &mut Self;
+}
+
+impl JsonAssetAppExt for App {
+    fn register_json_asset<T: Asset + DeserializeOwned>(
+        &mut self,
+        extensions: &'static [&'static str],
+    ) -> &mut Self {
+        self.init_asset::<T>()
+            .register_asset_loader(JsonAssetLoader::<T>::new(extensions))
+    }
+}
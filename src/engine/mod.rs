@@ -1 +1,3 @@
 pub mod damage;
+/// Loader for the JSON data assets
+pub mod json_asset;
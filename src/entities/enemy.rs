@@ -2,8 +2,12 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::{prelude::*, utils::ldtk_pixel_coords_to_translation_pivoted};
 use bevy_rapier2d::dynamics::Velocity;
 
-use crate::physics::colliders::ColliderBundle;
+use crate::engine::damage::InvincibilityDuration;
 use crate::entities::stats::Stats;
+use crate::physics::colliders::ColliderBundle;
+
+use super::loot::{Loot, LootPlugin};
+use crate::GameState;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Enemy;
@@ -21,6 +25,10 @@ pub struct EnemyBundle {
 
     #[from_entity_instance]
     pub stats: Stats,
+    pub invincibility_duration: InvincibilityDuration,
+
+    #[from_entity_instance]
+    pub loot: Loot,
 }
 
 #[derive(Clone, PartialEq, Debug, Default, Component)]
@@ -113,7 +121,8 @@ pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, patrol)
+        app.add_plugins(LootPlugin)
+            .add_systems(Update, patrol.run_if(in_state(GameState::InGame)))
             .register_ldtk_entity::<EnemyBundle>("Mob");
     }
 }
@@ -0,0 +1,256 @@
+//! Loot dropped by the mobs when they die.
+//!
+//! A mob drops the items listed in its LDtk `loot` field. Mobs without explicit loot may
+//! name a weighted drop table (LDtk `drop_table` field) from `assets/data/mobs.drops.json`.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_ecs_ldtk::prelude::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::Deserialize;
+
+use crate::core::inventory::{items_from_field, Item};
+use crate::engine::json_asset::JsonAssetAppExt;
+use crate::world::pickups::{item_icon, item_pickup_bundle, pop_velocities};
+
+use super::stats::Stats;
+
+/// Path of the drop tables in the assets
+const DROP_TABLES_PATH: &str = "data/mobs.drops.json";
+
+#[derive(Clone, Debug, Default, Component)]
+pub struct Loot {
+    /// Items always dropped
+    pub items: Vec<Item>,
+    /// Drop table rolled when there is no explicit loot
+    pub drop_table: Option<String>,
+}
+
+impl From<&EntityInstance> for Loot {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Loot {
+            items: items_from_field(entity_instance, "loot"),
+            drop_table: entity_instance
+                .get_maybe_string_field("drop_table")
+                .ok()
+                .cloned()
+                .flatten(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DropEntry {
+    /// `None` for a roll that drops nothing
+    #[serde(default)]
+    pub item: Option<Item>,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DropTable {
+    /// Number of times the table is rolled
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    pub entries: Vec<DropEntry>,
+}
+
+fn default_rolls() -> u32 {
+    1
+}
+
+impl DropTable {
+    /// Roll the table, returning the dropped items
+    pub fn roll(&self, rng: &mut impl rand::Rng) -> Vec<Item> {
+        let Ok(distribution) = WeightedIndex::new(self.entries.iter().map(|entry| entry.weight))
+        else {
+            return Vec::new();
+        };
+
+        (0..self.rolls)
+            .filter_map(|_| self.entries[distribution.sample(rng)].item)
+            .collect()
+    }
+}
+
+/// Drop tables by name, loaded from the assets
+#[derive(Asset, TypePath, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct DropTables(pub HashMap<String, DropTable>);
+
+/// Resource holding the handle of the drop tables
+#[derive(Resource, Default)]
+pub struct DropTablesHandle(pub Handle<DropTables>);
+
+fn load_drop_tables(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DropTablesHandle(asset_server.load(DROP_TABLES_PATH)));
+}
+
+/// Observer qui fait tomber le butin d'un ennemi à sa mort
+fn drop_loot(
+    trigger: Trigger<OnRemove, Loot>,
+    mut commands: Commands,
+    loot_query: Query<(&Loot, &Stats, &Transform, Option<&Parent>)>,
+    drop_tables_handle: Res<DropTablesHandle>,
+    drop_tables: Res<Assets<DropTables>>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    // Loot is also removed when the level unloads, only the dead drop it
+    let Ok((loot, stats, transform, parent)) = loot_query.get(trigger.entity()) else {
+        return;
+    };
+    if stats.life > 0 {
+        return;
+    }
+
+    let items = if !loot.items.is_empty() {
+        loot.items.clone()
+    } else if let Some(table_name) = &loot.drop_table {
+        match drop_tables
+            .get(&drop_tables_handle.0)
+            .and_then(|tables| tables.0.get(table_name))
+        {
+            Some(table) => table.roll(&mut rand::thread_rng()),
+            None => {
+                warn!("Unknown drop table {}", table_name);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let Some(ldtk_project) = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle))
+    else {
+        return;
+    };
+
+    let translation = transform.translation + Vec3::new(0., 4., 1.);
+    for (item, linvel) in items.iter().zip(pop_velocities(items.len())) {
+        let Some(sprite) = item_icon(ldtk_project, *item) else {
+            warn!("No icon found for item {:?}", item);
+            continue;
+        };
+
+        // Siblings of the mob so they unload with its level
+        let mut pickup = commands.spawn(item_pickup_bundle(*item, sprite, translation, linvel));
+        if let Some(parent) = parent {
+            pickup.set_parent(parent.get());
+        }
+    }
+}
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_json_asset::<DropTables>(&["drops.json"])
+            .init_resource::<DropTablesHandle>()
+            .add_systems(Startup, load_drop_tables)
+            .add_observer(drop_loot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::engine::damage::DamageEvent;
+    use crate::entities::enemy::Enemy;
+    use crate::testing::TestGame;
+    use crate::world::pickups::ItemPickup;
+
+    fn entry(item: Option<Item>, weight: u32) -> DropEntry {
+        DropEntry { item, weight }
+    }
+
+    #[test]
+    fn drop_table_rolls_follow_the_weights() {
+        let table = DropTable {
+            rolls: 10_000,
+            entries: vec![
+                entry(Some(Item::Meat), 3),
+                entry(Some(Item::Gem), 1),
+                entry(Some(Item::Boots), 0),
+                entry(None, 4),
+            ],
+        };
+
+        let items = table.roll(&mut StdRng::seed_from_u64(7));
+        let count = |item| items.iter().filter(|dropped| **dropped == item).count();
+
+        // 3/8 of the rolls drop meat, 1/8 a gem and the rest nothing
+        assert!(
+            (3_500..4_000).contains(&count(Item::Meat)),
+            "{}",
+            count(Item::Meat)
+        );
+        assert!(
+            (1_050..1_450).contains(&count(Item::Gem)),
+            "{}",
+            count(Item::Gem)
+        );
+        assert_eq!(count(Item::Boots), 0);
+        assert_eq!(items.len(), count(Item::Meat) + count(Item::Gem));
+    }
+
+    #[test]
+    fn drop_table_without_weights_drops_nothing() {
+        let table = DropTable {
+            rolls: 3,
+            entries: vec![entry(Some(Item::Gem), 0)],
+        };
+
+        assert!(table.roll(&mut StdRng::seed_from_u64(7)).is_empty());
+    }
+
+    fn pickups(game: &mut TestGame) -> Vec<Item> {
+        game.app
+            .world_mut()
+            .query::<&ItemPickup>()
+            .iter(game.app.world())
+            .map(|pickup| pickup.item)
+            .collect()
+    }
+
+    fn mob_with_loot(game: &mut TestGame) -> Entity {
+        game.settle();
+        let mob = game.find::<Enemy>().unwrap();
+        game.get_mut::<Loot>(mob).items = vec![Item::Gem];
+        mob
+    }
+
+    #[test]
+    fn killed_mob_drops_its_loot() {
+        let mut game = TestGame::new();
+        let mob = mob_with_loot(&mut game);
+        let player = game.player();
+
+        game.app
+            .world_mut()
+            .send_event(DamageEvent::new(mob, player, 100));
+        game.step(5);
+
+        assert!(game.get::<Enemy>(mob).is_none());
+        assert_eq!(pickups(&mut game), vec![Item::Gem]);
+    }
+
+    #[test]
+    fn mob_unloaded_with_its_level_drops_nothing() {
+        let mut game = TestGame::new();
+        mob_with_loot(&mut game);
+
+        let level = game.find::<LevelIid>().unwrap();
+        game.app.world_mut().entity_mut(level).insert(Respawn);
+        game.settle();
+
+        assert!(game.find::<Enemy>().is_some());
+        assert!(pickups(&mut game).is_empty());
+    }
+}
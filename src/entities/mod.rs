@@ -1,4 +1,7 @@
 pub mod enemy;
+pub mod loot;
+/// Data-driven tuning of the player movement
+pub mod movement;
 pub mod player;
 pub mod player_animation;
 pub mod stats;
@@ -0,0 +1,210 @@
+//! Data-driven tuning of the player movement.
+//!
+//! Speeds, acceleration, jump and gravity are read from `assets/data/player.movement.json`.
+//! Missing fields keep their default value, and the file is applied again whenever it is
+//! modified (with the `hot_reload` feature), so the feel of the game can be tuned while
+//! it runs.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::engine::json_asset::JsonAssetAppExt;
+
+/// Path of the movement profile in the assets
+const MOVEMENT_PROFILE_PATH: &str = "data/player.movement.json";
+
+/// Réglages du déplacement et du saut du joueur
+#[derive(Clone, PartialEq, Debug, Component, Deserialize)]
+#[serde(default)]
+pub struct Movement {
+    /// Horizontal speed (in pixels per second)
+    pub run_speed: f32,
+    /// Speed gained per second while running on the ground
+    pub acceleration: f32,
+    /// Speed lost per second when stopping or turning on the ground
+    pub deceleration: f32,
+    /// Part of the acceleration and deceleration kept in the air
+    pub air_control: f32,
+    pub climb_speed: f32,
+    /// Vertical speed given by a jump
+    pub jump_speed: f32,
+    /// Time after walking off a ledge during which the player can still jump (in seconds)
+    pub coyote_time: f32,
+    /// A jump pressed this long before landing happens on landing (in seconds)
+    pub jump_buffer: f32,
+    /// Part of the upward speed kept when the jump button is released early (short hop)
+    pub jump_cut: f32,
+    pub max_fall_speed: f32,
+    /// Fall speed while sliding down a wall, pushing against it
+    pub wall_slide_speed: f32,
+    /// Horizontal speed of a wall jump, away from the wall
+    pub wall_jump_push: f32,
+    /// Time after a wall jump during which the horizontal input is ignored (in seconds)
+    pub wall_jump_lockout: f32,
+    /// Speed of the recoil when the player touches a mob
+    pub hurt_knockback: f32,
+    /// Speed of the recoil given by the attacks of the player
+    pub attack_knockback: f32,
+}
+
+impl Default for Movement {
+    fn default() -> Self {
+        Self {
+            run_speed: 200.,
+            acceleration: 2400.,
+            deceleration: 3000.,
+            air_control: 0.65,
+            climb_speed: 200.,
+            jump_speed: 500.,
+            coyote_time: 0.1,
+            jump_buffer: 0.12,
+            jump_cut: 0.45,
+            max_fall_speed: 900.,
+            wall_slide_speed: 120.,
+            wall_jump_push: 260.,
+            wall_jump_lockout: 0.15,
+            hurt_knockback: 400.,
+            attack_knockback: 350.,
+        }
+    }
+}
+
+impl Movement {
+    /// Horizontal speed after `delta` seconds of running towards `target`
+    pub fn horizontal_speed(&self, current: f32, target: f32, airborne: bool, delta: f32) -> f32 {
+        let speeding_up = target != 0. && target * current >= 0. && target.abs() > current.abs();
+        let rate = if speeding_up {
+            self.acceleration
+        } else {
+            self.deceleration
+        };
+        let control = if airborne { self.air_control } else { 1. };
+
+        let max_change = rate * control * delta;
+        current + (target - current).clamp(-max_change, max_change)
+    }
+}
+
+/// Profil de déplacement, chargé depuis les assets
+#[derive(Asset, TypePath, Clone, PartialEq, Debug, Deserialize)]
+#[serde(default)]
+pub struct MovementProfile {
+    /// Gravity of the world (in pixels per second squared, pulling down)
+    pub gravity: f32,
+    #[serde(flatten)]
+    pub player: Movement,
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self {
+            gravity: 2000.,
+            player: Movement::default(),
+        }
+    }
+}
+
+/// Resource holding the handle of the movement profile
+#[derive(Resource, Default)]
+pub struct MovementProfileHandle(pub Handle<MovementProfile>);
+
+impl MovementProfileHandle {
+    /// The loaded profile, the default one while it is loading or if it is invalid
+    pub fn profile(&self, profiles: &Assets<MovementProfile>) -> MovementProfile {
+        profiles.get(&self.0).cloned().unwrap_or_default()
+    }
+}
+
+fn load_movement_profile(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MovementProfileHandle(
+        asset_server.load(MOVEMENT_PROFILE_PATH),
+    ));
+}
+
+/// Système qui applique le profil de déplacement quand il est chargé ou modifié, et aux
+/// joueurs qui viennent d'apparaître
+fn apply_movement_profile(
+    mut asset_events: EventReader<AssetEvent<MovementProfile>>,
+    handle: Res<MovementProfileHandle>,
+    profiles: Res<Assets<MovementProfile>>,
+    mut movements: Query<&mut Movement>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    let reloaded = asset_events
+        .read()
+        .filter(|event| {
+            event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0)
+        })
+        .count()
+        > 0;
+    let Some(profile) = profiles.get(&handle.0) else {
+        return;
+    };
+
+    for mut movement in movements.iter_mut() {
+        if reloaded || movement.is_added() {
+            *movement = profile.player.clone();
+        }
+    }
+
+    if reloaded {
+        for mut config in rapier_config.iter_mut() {
+            config.gravity = Vec2::new(0., -profile.gravity);
+        }
+        info!("Profil de déplacement appliqué");
+    }
+}
+
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_json_asset::<MovementProfile>(&["movement.json"])
+            .init_resource::<MovementProfileHandle>()
+            .add_systems(Startup, load_movement_profile)
+            .add_systems(Update, apply_movement_profile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_keep_their_default_value() {
+        let profile: MovementProfile =
+            serde_json::from_str(r#"{ "gravity": 1500, "jump_speed": 420 }"#).unwrap();
+
+        assert_eq!(profile.gravity, 1500.);
+        assert_eq!(profile.player.jump_speed, 420.);
+        assert_eq!(profile.player.run_speed, Movement::default().run_speed);
+    }
+
+    #[test]
+    fn the_shipped_profile_is_valid() {
+        let profile: MovementProfile =
+            serde_json::from_str(include_str!("../../assets/data/player.movement.json")).unwrap();
+
+        assert!(profile.gravity > 0.);
+    }
+
+    #[test]
+    fn running_speed_ramps_up_and_down() {
+        let movement = Movement {
+            run_speed: 200.,
+            acceleration: 1000.,
+            deceleration: 2000.,
+            air_control: 0.5,
+            ..default()
+        };
+
+        assert_eq!(movement.horizontal_speed(0., 200., false, 0.1), 100.);
+        assert_eq!(movement.horizontal_speed(150., 200., false, 0.1), 200.);
+        assert_eq!(movement.horizontal_speed(200., 0., false, 0.05), 100.);
+        // Turning around brakes first
+        assert_eq!(movement.horizontal_speed(100., -200., false, 0.1), -100.);
+        // Less control in the air
+        assert_eq!(movement.horizontal_speed(0., 200., true, 0.1), 50.);
+    }
+}
@@ -1,56 +1,27 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::dynamics::Velocity;
+use bevy_rapier2d::geometry::{Group, SolverGroups};
 
-use crate::config::KeyBindings;
+use crate::core::inventory::Inventory;
+use crate::core::item_effects::PlayerModifiers;
+use crate::engine::damage::InvincibilityDuration;
 use crate::gui::player_interface::PlayerInterfacePlugin;
-use crate::input::{
-    get_left_stick_x, get_left_stick_y, is_button_just_pressed, GamepadState,
-    STICK_NAVIGATION_THRESHOLD,
-};
-use crate::GameState;
+use crate::input::actions::{Action, ActionState};
+use crate::input::STICK_NAVIGATION_THRESHOLD;
 use crate::physics::climbing::Climber;
-use crate::core::inventory::Inventory;
-use crate::physics::colliders::ColliderBundle;
+use crate::physics::colliders::{ColliderBundle, PICKUP_GROUP};
 use crate::physics::ground_detection::GroundDetection;
+use crate::physics::one_way_platforms::PlatformDrop;
+use crate::physics::wall_detection::WallDetection;
+use crate::world::hazards::SafeGround;
+use crate::world::interaction::{find_interaction_target, InteractEvent, Interactable};
+use crate::GameState;
 
+use super::movement::{Movement, MovementPlugin};
 use super::player_animation::PlayerAnimationPlugin;
 use super::stats::Stats;
 
-/// Composant pour gérer l'invincibilité temporaire après avoir pris des dégâts
-#[derive(Component)]
-pub struct Invincibility {
-    pub timer: Timer,
-    pub blink_timer: Timer,
-    pub visible: bool,
-}
-
-impl Default for Invincibility {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(3.0, TimerMode::Once),
-            blink_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            visible: true,
-        }
-    }
-}
-
-/// Composant pour gérer le recul (knockback)
-#[derive(Component)]
-pub struct Knockback {
-    pub velocity: Vec2,
-    pub timer: Timer,
-}
-
-impl Knockback {
-    pub fn new(direction: Vec2, force: f32) -> Self {
-        Self {
-            velocity: direction.normalize_or_zero() * force,
-            timer: Timer::from_seconds(0.2, TimerMode::Once),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States, Component)]
 pub enum Side {
     #[default]
@@ -61,6 +32,45 @@ pub enum Side {
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Player;
 
+/// Événement envoyé à chaque saut du joueur (depuis le sol, une échelle ou en l'air)
+#[derive(Event, Debug, Clone)]
+pub struct JumpEvent {
+    pub entity: Entity,
+}
+
+/// Nombre de sauts effectués en l'air depuis le dernier contact avec le sol
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct AirJumps(pub u32);
+
+/// Progression du saut en cours, utilisée par le temps de grâce et le saut variable
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct JumpState {
+    /// Time since the player last stood on the ground or on a ladder (in seconds)
+    pub airborne_time: f32,
+    /// Time left before a buffered jump press is dropped (in seconds)
+    pub buffered: f32,
+    /// Rising from a jump whose button is still held
+    pub rising: bool,
+    /// Time left before the horizontal input is read again after a wall jump (in seconds)
+    pub wall_jump_lockout: f32,
+    /// Horizontal speed kept from the moving platform jumped from, until landing
+    pub carried_speed: f32,
+}
+
+impl JumpState {
+    /// Vertical speed of a jump starting now. It rises until its button is released, a
+    /// buffered tap already released is cut short right away
+    fn start_jump(&mut self, movement: &Movement, held: bool) -> f32 {
+        self.buffered = 0.;
+        self.rising = held;
+        if held {
+            movement.jump_speed
+        } else {
+            movement.jump_speed * movement.jump_cut
+        }
+    }
+}
+
 #[derive(Clone, Default, Bundle, LdtkEntity)]
 pub struct PlayerBundle {
     #[from_entity_instance]
@@ -70,9 +80,19 @@ pub struct PlayerBundle {
     pub worldly: Worldly,
     pub climber: Climber,
     pub ground_detection: GroundDetection,
+    pub wall_detection: WallDetection,
+    pub platform_drop: PlatformDrop,
+    pub safe_ground: SafeGround,
+    pub air_jumps: AirJumps,
+    pub movement: Movement,
+    pub jump_state: JumpState,
     pub side: Side,
     #[from_entity_instance]
     pub stats: Stats,
+    #[with(player_invincibility_duration)]
+    pub invincibility_duration: InvincibilityDuration,
+    #[with(player_solver_groups)]
+    pub solver_groups: SolverGroups,
 
     // Build Items Component manually by using `impl From<&EntityInstance>`
     #[from_entity_instance]
@@ -83,208 +103,484 @@ pub struct PlayerBundle {
     entity_instance: EntityInstance,
 }
 
+fn player_invincibility_duration(_: &EntityInstance) -> InvincibilityDuration {
+    InvincibilityDuration::PLAYER
+}
+
+/// The player walks through item pickups instead of pushing them
+fn player_solver_groups(_: &EntityInstance) -> SolverGroups {
+    SolverGroups::new(Group::ALL, Group::ALL ^ PICKUP_GROUP)
+}
+
 pub fn player_movement(
-    input: Res<ButtonInput<KeyCode>>,
-    key_bindings: Res<KeyBindings>,
-    gamepad_state: Res<GamepadState>,
-    gamepads: Query<&Gamepad>,
-    mut query: Query<(&mut Velocity, &mut Climber, &GroundDetection, &mut Side), With<Player>>,
+    time: Res<Time>,
+    action_state: Res<ActionState>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Velocity,
+            &mut Climber,
+            &GroundDetection,
+            &WallDetection,
+            &mut PlatformDrop,
+            &mut Side,
+            &mut AirJumps,
+            &Movement,
+            &mut JumpState,
+            Option<&PlayerModifiers>,
+        ),
+        With<Player>,
+    >,
+    mut jump_events: EventWriter<JumpEvent>,
 ) {
-    for (mut velocity, mut climber, ground_detection, mut side) in &mut query {
-        let (right, left, up, down, jump) =
-            if let Some(gamepad_entity) = gamepad_state.active_gamepad {
-                // Gamepad input (has priority)
-                let stick_x = get_left_stick_x(&gamepads, gamepad_entity);
-                let stick_y = get_left_stick_y(&gamepads, gamepad_entity);
-
-                let right = if stick_x > 0.0 { stick_x } else { 0.0 };
-                let left = if stick_x < 0.0 { -stick_x } else { 0.0 };
-                let up = if stick_y > 0.0 { stick_y } else { 0.0 };
-                let down = if stick_y < 0.0 { -stick_y } else { 0.0 };
-                let jump = is_button_just_pressed(
-                    &gamepads,
-                    gamepad_entity,
-                    GamepadButton::South,
-                );
+    let delta = time.delta_secs();
+
+    for (
+        entity,
+        mut velocity,
+        mut climber,
+        ground_detection,
+        wall_detection,
+        mut platform_drop,
+        mut side,
+        mut air_jumps,
+        movement,
+        mut jump_state,
+        modifiers,
+    ) in &mut query
+    {
+        let modifiers = modifiers.copied().unwrap_or_default();
+
+        let horizontal = action_state.axis(Action::MoveLeft, Action::MoveRight);
+        let up = action_state.value(Action::MoveUp);
+        let down = action_state.value(Action::MoveDown);
+        let jump = action_state.just_pressed(Action::Jump);
+        let jump_held = action_state.pressed(Action::Jump);
+
+        // Bas + saut sur une plateforme : le joueur la traverse au lieu de sauter
+        let drop_through =
+            jump && down > STICK_NAVIGATION_THRESHOLD && ground_detection.on_platform;
+        if drop_through {
+            platform_drop.remaining = PlatformDrop::DURATION;
+            // No coyote jump when dropping on purpose
+            jump_state.airborne_time = f32::INFINITY;
+        }
+        let jump = jump && !drop_through;
 
-                (right, left, up, down, jump)
-            } else {
-                // Keyboard input (fallback)
-                let right = if input.pressed(key_bindings.move_right) {
-                    1.0
-                } else {
-                    0.0
-                };
-                let left = if input.pressed(key_bindings.move_left) {
-                    1.0
-                } else {
-                    0.0
-                };
-                let up = if input.pressed(key_bindings.move_up) {
-                    1.0
-                } else {
-                    0.0
-                };
-                let down = if input.pressed(key_bindings.move_down) {
-                    1.0
-                } else {
-                    0.0
-                };
-                let jump = input.just_pressed(key_bindings.jump);
-
-                (right, left, up, down, jump)
-            };
+        // Bas sur le haut d'une échelle : le joueur la traverse pour la descendre
+        if climber.on_ladder_top && !jump && down > STICK_NAVIGATION_THRESHOLD {
+            platform_drop.remaining = PlatformDrop::DURATION;
+        }
 
-        velocity.linvel.x = (right - left) * 200.;
+        let airborne = !ground_detection.on_ground && !climber.climbing;
+        let ground_velocity = ground_detection.ground_velocity.unwrap_or_default();
+        // The ground is still detected for a few frames after a jump
+        let leaving_ground = velocity.linvel.y > ground_velocity.y + 1.;
+        if (ground_detection.on_ground && !leaving_ground) || climber.climbing {
+            jump_state.carried_speed = 0.;
+        }
+        // The push of a wall jump is not cancelled by the player still holding towards the wall
+        jump_state.wall_jump_lockout = (jump_state.wall_jump_lockout - delta).max(0.);
+        // The player only leaves a ladder sideways by jumping off, or at its foot
+        let on_ladder = climber.climbing && !ground_detection.on_ground;
+        let horizontal = if jump_state.wall_jump_lockout > 0. || on_ladder {
+            0.
+        } else {
+            horizontal
+        };
 
-        if right > 0.0 || left > 0.0 {
-            if right > left {
-                *side = Side::Right;
-            } else {
-                *side = Side::Left;
-            }
+        let target_speed = horizontal * movement.run_speed * modifiers.move_speed_multiplier;
+        if on_ladder {
+            velocity.linvel.x = 0.;
+        } else if jump_state.wall_jump_lockout == 0. {
+            // Running is relative to the speed kept from a moving platform
+            let carried = jump_state.carried_speed;
+            velocity.linvel.x = carried
+                + movement.horizontal_speed(
+                    velocity.linvel.x - carried,
+                    target_speed,
+                    airborne,
+                    delta,
+                );
+        }
+
+        if horizontal > 0.0 {
+            *side = Side::Right;
+        } else if horizontal < 0.0 {
+            *side = Side::Left;
         }
 
         if climber.intersecting_climbables.is_empty() {
             climber.climbing = false;
         } else if up > 0.0 || down > 0.0 {
             // Start climbing when moving up or down on climbable surface
-            if !climber.climbing
-                && (up > STICK_NAVIGATION_THRESHOLD || down > STICK_NAVIGATION_THRESHOLD)
-            {
+            // Standing on the top of the ladder, there is nothing left to climb up
+            let climbing_up = up > STICK_NAVIGATION_THRESHOLD && !climber.on_ladder_top;
+            if !climber.climbing && (climbing_up || down > STICK_NAVIGATION_THRESHOLD) {
                 climber.climbing = true;
             }
         }
 
         if climber.climbing {
-            velocity.linvel.y = (up - down) * 200.;
+            velocity.linvel.y =
+                action_state.axis(Action::MoveDown, Action::MoveUp) * movement.climb_speed;
+        }
+        velocity.linvel.y = velocity.linvel.y.max(-movement.max_fall_speed);
+
+        let wall_side = if airborne {
+            wall_detection.wall_side(*side)
+        } else {
+            None
+        };
+        // Glissade le long d'un mur contre lequel le joueur pousse
+        let pushing_wall = match wall_side {
+            Some(Side::Left) => horizontal < 0.0,
+            Some(Side::Right) => horizontal > 0.0,
+            None => false,
+        };
+        if pushing_wall {
+            velocity.linvel.y = velocity.linvel.y.max(-movement.wall_slide_speed);
+        }
+
+        let on_ground = ground_detection.on_ground && !platform_drop.dropping();
+        if on_ground || climber.climbing {
+            air_jumps.0 = 0;
+            jump_state.airborne_time = 0.;
+        } else {
+            jump_state.airborne_time += delta;
+        }
+
+        if jump {
+            jump_state.buffered = movement.jump_buffer;
+        } else {
+            jump_state.buffered = (jump_state.buffered - delta).max(0.);
         }
 
-        if jump && (ground_detection.on_ground || climber.climbing) {
-            velocity.linvel.y = 500.;
+        // Sol, échelle, ou bord quitté depuis moins que le temps de grâce
+        let can_jump = jump_state.airborne_time <= movement.coyote_time;
+
+        if jump_state.buffered > 0. && can_jump {
+            // Un saut depuis une plateforme mobile garde sa vitesse
+            velocity.linvel.x += ground_velocity.x;
+            velocity.linvel.y = jump_state.start_jump(movement, jump_held) + ground_velocity.y;
+            jump_state.carried_speed = ground_velocity.x;
             climber.climbing = false;
+            // The grace time is spent by this jump
+            jump_state.airborne_time = f32::INFINITY;
+            jump_events.send(JumpEvent { entity });
+        } else if let (true, Some(wall)) = (jump_state.buffered > 0., wall_side) {
+            // Saut mural : repousse le joueur loin du mur
+            let away = match wall {
+                Side::Left => Side::Right,
+                Side::Right => Side::Left,
+            };
+            velocity.linvel.x = match away {
+                Side::Right => movement.wall_jump_push,
+                Side::Left => -movement.wall_jump_push,
+            };
+            velocity.linvel.y = jump_state.start_jump(movement, jump_held);
+            *side = away;
+            air_jumps.0 = 0;
+            jump_state.carried_speed = 0.;
+            jump_state.wall_jump_lockout = movement.wall_jump_lockout;
+            jump_events.send(JumpEvent { entity });
+        } else if jump && air_jumps.0 < modifiers.extra_jumps {
+            // Saut supplémentaire en l'air (bottes)
+            air_jumps.0 += 1;
+            velocity.linvel.y = jump_state.start_jump(movement, jump_held);
+            jump_events.send(JumpEvent { entity });
+        } else if jump_state.rising {
+            // Releasing the button early cuts the jump short
+            if velocity.linvel.y <= 0. || climber.climbing {
+                jump_state.rising = false;
+            } else if action_state.just_released(Action::Jump) {
+                velocity.linvel.y *= movement.jump_cut;
+                jump_state.rising = false;
+            }
         }
     }
 }
 
 pub fn player_actions(
-    input: Res<ButtonInput<KeyCode>>,
-    key_bindings: Res<KeyBindings>,
-    gamepad_state: Res<GamepadState>,
-    gamepads: Query<&Gamepad>,
-    mut query: Query<(&Climber, &GroundDetection), With<Player>>,
+    action_state: Res<ActionState>,
+    mut query: Query<(Entity, &GlobalTransform, &Climber, &GroundDetection), With<Player>>,
+    interactables: Query<(Entity, &GlobalTransform), With<Interactable>>,
+    mut interact_events: EventWriter<InteractEvent>,
 ) {
-    for (climber, ground_detection) in &mut query {
+    for (player_entity, transform, climber, ground_detection) in &mut query {
         if climber.climbing {
             return;
         }
 
-        let interact = if let Some(gamepad_entity) = gamepad_state.active_gamepad {
-            // Gamepad input (has priority) - use East button (B/Circle) for interact
-            is_button_just_pressed(&gamepads, gamepad_entity, GamepadButton::East)
-        } else {
-            // Keyboard input (fallback)
-            input.just_pressed(key_bindings.interact)
-        };
-
-        if interact && ground_detection.on_ground {
-            dbg!("Open element");
+        if action_state.just_pressed(Action::Interact) && ground_detection.on_ground {
+            if let Some(target) =
+                find_interaction_target(transform.translation().truncate(), &interactables)
+            {
+                interact_events.send(InteractEvent {
+                    player: player_entity,
+                    target,
+                });
+            }
         }
         // L'attaque est maintenant gérée par le CombatPlugin
     }
 }
 
-pub fn check_player_death(
-    query: Query<&Stats, With<Player>>,
-    mut next_state: ResMut<NextState<GameState>>,
-) {
-    for stats in query.iter() {
-        if stats.life <= 0 {
-            info!("Le joueur est mort ! Game Over");
-            next_state.set(GameState::GameOver);
-        }
-    }
-}
-
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (player_movement, player_actions).run_if(in_state(GameState::InGame)),
-        )
-        .register_ldtk_entity::<PlayerBundle>("Player")
-        .add_plugins(PlayerInterfacePlugin)
-        .add_plugins(PlayerAnimationPlugin)
-        .add_systems(
-            Update,
-            (
-                update_invincibility,
-                update_knockback,
-                update_invincibility_blink,
+        app.add_event::<JumpEvent>()
+            .add_systems(
+                Update,
+                (player_movement, player_actions).run_if(in_state(GameState::InGame)),
             )
-                .run_if(in_state(GameState::InGame)),
-        )
-        .add_systems(
-            Update,
-            check_player_death.run_if(in_state(GameState::InGame)),
-        );
+            .register_ldtk_entity::<PlayerBundle>("Player")
+            .add_plugins(MovementPlugin)
+            .add_plugins(PlayerInterfacePlugin)
+            .add_plugins(PlayerAnimationPlugin);
     }
 }
 
-/// Système qui met à jour le timer d'invincibilité
-pub fn update_invincibility(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Invincibility, &mut Sprite), With<Player>>,
-) {
-    for (entity, mut invincibility, mut sprite) in query.iter_mut() {
-        invincibility.timer.tick(time.delta());
-
-        if invincibility.timer.finished() {
-            // Retirer l'invincibilité et s'assurer que le sprite est visible
-            commands.entity(entity).remove::<Invincibility>();
-            sprite.color = Color::WHITE;
-            info!("Fin de l'invincibilité");
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_rapier2d::dynamics::Velocity;
+
+    use super::JumpEvent;
+    use crate::physics::ground_detection::GroundDetection;
+    use crate::physics::wall_detection::WallDetection;
+    use crate::testing::TestGame;
+
+    /// Highest point reached within `frames` frames, relative to the start
+    fn peak_height(game: &mut TestGame, player: Entity, frames: u32) -> f32 {
+        let start = game.position(player).y;
+        let mut peak = start;
+        for _ in 0..frames {
+            game.step(1);
+            peak = peak.max(game.position(player).y);
         }
+        peak - start
     }
-}
 
-/// Système qui fait clignoter le joueur pendant l'invincibilité
-pub fn update_invincibility_blink(
-    time: Res<Time>,
-    mut query: Query<(&mut Invincibility, &mut Sprite), With<Player>>,
-) {
-    for (mut invincibility, mut sprite) in query.iter_mut() {
-        invincibility.blink_timer.tick(time.delta());
+    #[test]
+    fn player_walks_with_the_move_keys() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let start = game.position(player);
 
-        if invincibility.blink_timer.just_finished() {
-            invincibility.visible = !invincibility.visible;
+        game.press_key(KeyCode::KeyD);
+        game.step(30);
+        game.release_key(KeyCode::KeyD);
+        game.step(1);
 
-            if invincibility.visible {
-                sprite.color = Color::WHITE;
-            } else {
-                sprite.color = Color::srgba(1.0, 1.0, 1.0, 0.3);
-            }
-        }
+        let moved = game.position(player).x - start.x;
+        assert!(moved > 50.0, "player should walk right, moved {moved}");
     }
-}
 
-/// Système qui applique le knockback (recul)
-pub fn update_knockback(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Knockback, &mut Velocity), With<Player>>,
-) {
-    for (entity, mut knockback, mut velocity) in query.iter_mut() {
-        knockback.timer.tick(time.delta());
+    #[test]
+    fn player_jumps_from_the_ground_and_lands() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        assert!(game.get::<GroundDetection>(player).unwrap().on_ground);
+        let ground_y = game.position(player).y;
+
+        game.press_key(KeyCode::Space);
+        game.step(10);
+        game.release_key(KeyCode::Space);
+        let jump_height = game.position(player).y - ground_y;
+        assert!(jump_height > 20.0, "player should jump, rose {jump_height}");
+
+        game.settle();
+        assert!((game.position(player).y - ground_y).abs() < 1.0);
+        assert!(game.get::<GroundDetection>(player).unwrap().on_ground);
+    }
 
-        if knockback.timer.finished() {
-            commands.entity(entity).remove::<Knockback>();
-        } else {
-            // Appliquer la vélocité de recul
-            velocity.linvel = knockback.velocity;
+    #[test]
+    fn player_cannot_jump_in_the_air_without_boots() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+
+        game.tap_key(KeyCode::Space);
+        game.step(10);
+        let velocity_before = game.get::<Velocity>(player).unwrap().linvel.y;
+        game.tap_key(KeyCode::Space);
+        let velocity_after = game.get::<Velocity>(player).unwrap().linvel.y;
+
+        assert!(velocity_after <= velocity_before);
+    }
+
+    #[test]
+    fn gamepad_moves_and_jumps_the_player() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let gamepad = game.connect_gamepad();
+        let start = game.position(player);
+
+        game.set_axis(gamepad, GamepadAxis::LeftStickX, 1.0);
+        game.step(30);
+        game.set_axis(gamepad, GamepadAxis::LeftStickX, 0.0);
+        game.step(1);
+        assert!(game.position(player).x - start.x > 50.0);
+
+        game.settle();
+        let ground_y = game.position(player).y;
+        game.press_button(gamepad, GamepadButton::South);
+        game.step(10);
+        game.release_button(gamepad, GamepadButton::South);
+        assert!(game.position(player).y - ground_y > 20.0);
+    }
+
+    #[test]
+    fn keyboard_still_works_with_a_gamepad_connected() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        game.connect_gamepad();
+        let start = game.position(player);
+
+        game.press_key(KeyCode::KeyD);
+        game.step(30);
+        game.release_key(KeyCode::KeyD);
+        game.step(1);
+
+        assert!(game.position(player).x - start.x > 50.0);
+    }
+
+    #[test]
+    fn holding_jump_jumps_higher_than_a_tap() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+
+        game.tap_key(KeyCode::Space);
+        let short_hop = peak_height(&mut game, player, 40);
+        game.settle();
+
+        game.press_key(KeyCode::Space);
+        let full_jump = peak_height(&mut game, player, 40);
+        game.release_key(KeyCode::Space);
+
+        assert!(short_hop > 5.0, "a tap should still jump, rose {short_hop}");
+        assert!(
+            full_jump > short_hop * 1.5,
+            "full jump {full_jump} should be higher than short hop {short_hop}"
+        );
+    }
+
+    #[test]
+    fn jump_pressed_just_before_landing_is_buffered() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let ground_y = game.position(player).y;
+        game.record::<JumpEvent>();
+
+        game.press_key(KeyCode::Space);
+        game.step(10);
+        game.release_key(KeyCode::Space);
+        // Falling, a few pixels above the ground
+        assert!(game.step_until(120, |game| {
+            let velocity = game.get::<Velocity>(player).unwrap().linvel.y;
+            velocity < 0.0 && game.position(player).y - ground_y < 15.0
+        }));
+        assert!(!game.get::<GroundDetection>(player).unwrap().on_ground);
+
+        game.tap_key(KeyCode::Space);
+        game.step(15);
+        assert_eq!(game.recorded::<JumpEvent>().len(), 2);
+    }
+
+    #[test]
+    fn buffered_tap_released_before_landing_is_a_short_hop() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let ground_y = game.position(player).y;
+
+        // Released once past its peak, the first jump is a full one
+        game.press_key(KeyCode::Space);
+        let full_jump = peak_height(&mut game, player, 20);
+        game.release_key(KeyCode::Space);
+        // Falling, a few pixels above the ground
+        assert!(game.step_until(120, |game| {
+            let velocity = game.get::<Velocity>(player).unwrap().linvel.y;
+            velocity < 0.0 && game.position(player).y - ground_y < 15.0
+        }));
+
+        game.tap_key(KeyCode::Space);
+        let mut peak = ground_y;
+        for _ in 0..40 {
+            game.step(1);
+            peak = peak.max(game.position(player).y);
         }
+        let buffered_hop = peak - ground_y;
+
+        assert!(buffered_hop > 5.0, "the buffered tap should jump");
+        assert!(
+            full_jump > buffered_hop * 1.5,
+            "full jump {full_jump} should be higher than buffered hop {buffered_hop}"
+        );
+    }
+
+    #[test]
+    fn player_can_still_jump_right_after_leaving_the_ground() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        game.record::<JumpEvent>();
+
+        // Off the ground for a few frames: within the coyote time
+        game.get_mut::<Transform>(player).translation.y += 100.0;
+        game.step(2);
+        assert!(!game.get::<GroundDetection>(player).unwrap().on_ground);
+        game.tap_key(KeyCode::Space);
+        assert_eq!(game.recorded::<JumpEvent>().len(), 1);
+
+        // Past the coyote time, still in the air: no jump
+        game.settle();
+        game.get_mut::<Transform>(player).translation.y += 100.0;
+        game.step(9);
+        assert!(!game.get::<GroundDetection>(player).unwrap().on_ground);
+        game.tap_key(KeyCode::Space);
+        assert_eq!(game.recorded::<JumpEvent>().len(), 1);
+    }
+
+    #[test]
+    fn pushing_against_a_wall_slows_the_fall_and_allows_a_wall_jump() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        game.record::<JumpEvent>();
+
+        game.press_key(KeyCode::KeyA);
+        assert!(game.step_until(120, |game| {
+            game.get::<WallDetection>(player).unwrap().on_wall_left
+        }));
+
+        game.get_mut::<Transform>(player).translation.y += 100.0;
+        game.step(30);
+        let slide_speed = game.get::<Velocity>(player).unwrap().linvel.y;
+        assert!(!game.get::<GroundDetection>(player).unwrap().on_ground);
+        // Capped by the system, then one physics step of gravity
+        assert!(
+            (-160.0..0.0).contains(&slide_speed),
+            "player should slide down the wall, falls at {slide_speed}"
+        );
+
+        game.tap_key(KeyCode::Space);
+        game.step(1);
+        game.release_key(KeyCode::KeyA);
+        let velocity = game.get::<Velocity>(player).unwrap().linvel;
+        assert!(
+            velocity.x > 100.0,
+            "wall jump should push away, got {velocity}"
+        );
+        assert!(velocity.y > 0.0);
+        assert_eq!(game.recorded::<JumpEvent>().len(), 1);
     }
 }
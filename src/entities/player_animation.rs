@@ -1,8 +1,11 @@
 use bevy::prelude::*;
 
 use super::player::{Player, Side};
+use crate::combat::attack::AttackState;
+use crate::engine::damage::Knockback;
 use crate::physics::climbing::Climber;
 use crate::physics::ground_detection::GroundDetection;
+use crate::GameState;
 
 /// États d'animation du joueur
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Component)]
@@ -109,12 +112,20 @@ pub fn update_player_animation_state(
             &bevy_rapier2d::dynamics::Velocity,
             &GroundDetection,
             &Climber,
+            Option<&AttackState>,
+            Has<Knockback>,
         ),
         With<Player>,
     >,
 ) {
-    for (mut anim_state, velocity, ground_detection, climber) in query.iter_mut() {
-        let new_state = if climber.climbing {
+    for (mut anim_state, velocity, ground_detection, climber, attack_state, hurt) in
+        query.iter_mut()
+    {
+        let new_state = if hurt {
+            PlayerAnimationState::Hurt
+        } else if attack_state.is_some_and(|attack_state| attack_state.is_attacking) {
+            PlayerAnimationState::Attack
+        } else if climber.climbing {
             PlayerAnimationState::Climb
         } else if !ground_detection.on_ground {
             if velocity.linvel.y > 0.0 {
@@ -186,7 +197,8 @@ impl Plugin for PlayerAnimationPlugin {
                 update_player_animation_state,
                 animate_player,
             )
-                .chain(),
+                .chain()
+                .run_if(in_state(GameState::InGame)),
         );
     }
 }
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Component, Debug, Eq, Default, PartialEq)]
+#[derive(Clone, Component, Debug, Eq, Default, PartialEq, Serialize, Deserialize)]
 pub struct Stats {
     pub life: i32,
     pub max_life: i32,
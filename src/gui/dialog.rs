@@ -5,7 +5,7 @@
 
 use bevy::prelude::*;
 
-use crate::GameState;
+use crate::{GameState, InGameplay};
 
 /// Event to show a dialog at the top of the screen
 #[derive(Event)]
@@ -50,21 +50,12 @@ pub struct DialogText;
 pub struct SpeakerText;
 
 /// Resource to track dialog UI entities
-#[derive(Resource)]
+#[derive(Resource, Default)]
 struct DialogUI {
     top_container: Option<Entity>,
     bottom_container: Option<Entity>,
 }
 
-impl Default for DialogUI {
-    fn default() -> Self {
-        Self {
-            top_container: None,
-            bottom_container: None,
-        }
-    }
-}
-
 /// Plugin for managing dialog windows
 pub struct DialogPlugin;
 
@@ -85,7 +76,7 @@ impl Plugin for DialogPlugin {
                 )
                     .run_if(in_state(GameState::InGame)),
             )
-            .add_systems(OnExit(GameState::InGame), cleanup_dialogs);
+            .add_systems(OnExit(InGameplay), cleanup_dialogs);
     }
 }
 
@@ -0,0 +1,603 @@
+//! Inventory overlay, opened on top of the game while physics and enemies are frozen.
+//!
+//! The items are displayed as a grid of slots using the icons of the LDtk `Item` enum
+//! (from the "MV Icons Complete Sheet" atlas). The selected slot follows the same model
+//! as the main menu: keyboard, gamepad and mouse all move a single selection index.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::inventory::{Inventory, Item, ItemUsed};
+use crate::core::item_effects::{ItemEffectTable, ItemEffects};
+use crate::entities::player::{Player, Side};
+use crate::input::actions::{Action, ActionMap, ActionState};
+use crate::input::{
+    get_left_stick_x, get_left_stick_y, is_button_just_pressed, GamepadState,
+    STICK_NAVIGATION_THRESHOLD,
+};
+use crate::menu::settings::keycode_to_string;
+use crate::world::pickups::{item_icon, item_icon_image, item_pickup_bundle, PickupDelay};
+use crate::GameState;
+
+/// Nombre de cases par ligne
+const SLOT_COLUMNS: usize = 6;
+const SLOT_SIZE: f32 = 64.0;
+const ICON_SIZE: f32 = 48.0;
+
+/// Délai avant de pouvoir ramasser un objet jeté (en secondes)
+const DROP_PICKUP_DELAY: f32 = 1.0;
+
+const NORMAL_SLOT: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_SLOT: Color = Color::srgb(0.25, 0.25, 0.25);
+const SELECTED_SLOT: Color = Color::srgb(0.3, 0.5, 0.8);
+const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
+
+/// Marqueur pour les entités de l'écran d'inventaire
+#[derive(Component)]
+pub struct InventoryScreen;
+
+/// Index de la case dans la grille (pour la navigation)
+#[derive(Component)]
+pub struct InventorySlotIndex(pub usize);
+
+/// Marqueur pour le texte décrivant l'objet sélectionné
+#[derive(Component)]
+pub struct SelectedItemText;
+
+/// Action de l'écran d'inventaire
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryAction {
+    Use,
+    Drop,
+    Close,
+}
+
+/// Événement envoyé quand une action de l'inventaire est validée
+#[derive(Event)]
+pub struct InventoryActionEvent(pub InventoryAction);
+
+/// Ressource pour suivre la case actuellement sélectionnée
+#[derive(Resource, Default)]
+pub struct SelectedInventorySlot {
+    pub index: usize,
+    pub total: usize,
+}
+
+pub struct InventoryScreenPlugin;
+
+impl Plugin for InventoryScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<InventoryActionEvent>()
+            .init_resource::<SelectedInventorySlot>()
+            .add_systems(
+                Update,
+                open_inventory.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                OnEnter(GameState::Inventory),
+                (freeze_physics, setup_inventory_screen),
+            )
+            .add_systems(
+                OnExit(GameState::Inventory),
+                (resume_physics, cleanup_inventory_screen),
+            )
+            .add_systems(
+                Update,
+                (
+                    inventory_navigation,
+                    inventory_shortcuts,
+                    slot_interactions,
+                    action_button_interactions,
+                    update_slot_visuals,
+                    update_selected_item_text,
+                    handle_inventory_actions,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Inventory)),
+            );
+    }
+}
+
+/// Ouvre l'inventaire avec l'action dédiée (touche I ou bouton Nord de la manette)
+fn open_inventory(action_state: Res<ActionState>, mut next_state: ResMut<NextState<GameState>>) {
+    if action_state.just_pressed(Action::Inventory) {
+        next_state.set(GameState::Inventory);
+    }
+}
+
+/// Met la simulation physique en pause pendant que l'inventaire est ouvert
+pub fn freeze_physics(mut rapier_config: Query<&mut RapierConfiguration>) {
+    for mut config in rapier_config.iter_mut() {
+        config.physics_pipeline_active = false;
+    }
+}
+
+pub fn resume_physics(mut rapier_config: Query<&mut RapierConfiguration>) {
+    for mut config in rapier_config.iter_mut() {
+        config.physics_pipeline_active = true;
+    }
+}
+
+fn setup_inventory_screen(
+    mut commands: Commands,
+    mut selected: ResMut<SelectedInventorySlot>,
+    player_query: Query<&Inventory, With<Player>>,
+    action_map: Res<ActionMap>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    selected.index = 0;
+
+    let inventory = player_query.get_single().cloned().unwrap_or_default();
+    let ldtk_project = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle));
+
+    spawn_inventory_screen(
+        &mut commands,
+        &mut selected,
+        &inventory,
+        ldtk_project,
+        &action_map,
+    );
+}
+
+/// Construit l'écran d'inventaire (reconstruit à chaque changement du contenu)
+fn spawn_inventory_screen(
+    commands: &mut Commands,
+    selected: &mut SelectedInventorySlot,
+    inventory: &Inventory,
+    ldtk_project: Option<&LdtkProject>,
+    action_map: &ActionMap,
+) {
+    let stacks = inventory.stacks();
+    selected.total = stacks.len();
+    selected.index = selected.index.min(selected.total.saturating_sub(1));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+            GlobalZIndex(100),
+            InventoryScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Inventaire"),
+                TextFont {
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            if inventory.is_empty() {
+                parent.spawn((
+                    Text::new("Votre inventaire est vide"),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                ));
+            }
+
+            // Grille des objets
+            parent
+                .spawn(Node {
+                    width: Val::Px(SLOT_COLUMNS as f32 * (SLOT_SIZE + 8.0)),
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::FlexStart,
+                    column_gap: Val::Px(8.0),
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (index, stack) in stacks.iter().enumerate() {
+                        let color = if index == selected.index {
+                            SELECTED_SLOT
+                        } else {
+                            NORMAL_SLOT
+                        };
+
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(SLOT_SIZE),
+                                    height: Val::Px(SLOT_SIZE),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(color),
+                                BorderRadius::all(Val::Px(8.0)),
+                                InventorySlotIndex(index),
+                            ))
+                            .with_children(|parent| {
+                                if let Some((image, rect)) = ldtk_project
+                                    .and_then(|project| item_icon_image(project, stack.item))
+                                {
+                                    parent.spawn((
+                                        ImageNode {
+                                            image,
+                                            rect: Some(rect),
+                                            ..default()
+                                        },
+                                        Node {
+                                            width: Val::Px(ICON_SIZE),
+                                            height: Val::Px(ICON_SIZE),
+                                            ..default()
+                                        },
+                                    ));
+                                }
+
+                                // Nombre d'objets de la pile
+                                parent.spawn((
+                                    Text::new(format!("x{}", stack.count)),
+                                    TextFont {
+                                        font_size: 14.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::WHITE),
+                                    Node {
+                                        position_type: PositionType::Absolute,
+                                        right: Val::Px(4.0),
+                                        bottom: Val::Px(2.0),
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                SelectedItemText,
+            ));
+
+            // Boutons d'action (souris)
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, action) in [
+                        ("Utiliser", InventoryAction::Use),
+                        ("Jeter", InventoryAction::Drop),
+                        ("Fermer", InventoryAction::Close),
+                    ] {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(150.0),
+                                    height: Val::Px(45.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(NORMAL_SLOT),
+                                BorderRadius::all(Val::Px(8.0)),
+                                action,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(label),
+                                    TextFont {
+                                        font_size: 22.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                ));
+                            });
+                    }
+                });
+
+            // Instructions de navigation
+            parent.spawn((
+                Text::new(format!(
+                    "Flèches : Naviguer  |  Entrée : Utiliser  |  X : Jeter  |  Échap/{} : Fermer",
+                    action_map
+                        .key(Action::Inventory)
+                        .map(keycode_to_string)
+                        .unwrap_or_default()
+                )),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.5, 0.5, 0.5)),
+            ));
+        });
+}
+
+fn cleanup_inventory_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<InventoryScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Navigation clavier et manette dans la grille
+fn inventory_navigation(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut selected: ResMut<SelectedInventorySlot>,
+    mut was_stick_navigating: Local<bool>,
+) {
+    if selected.total == 0 {
+        return;
+    }
+
+    let mut direction = IVec2::ZERO;
+
+    if let Some(gamepad_entity) = gamepad_state.active_gamepad {
+        // Gamepad navigation (has priority)
+        for (button, step) in [
+            (GamepadButton::DPadLeft, IVec2::NEG_X),
+            (GamepadButton::DPadRight, IVec2::X),
+            (GamepadButton::DPadUp, IVec2::NEG_Y),
+            (GamepadButton::DPadDown, IVec2::Y),
+        ] {
+            if is_button_just_pressed(&gamepads, gamepad_entity, button) {
+                direction = step;
+            }
+        }
+
+        // Left stick with debounce: only move when the stick first leaves the center
+        let stick = Vec2::new(
+            get_left_stick_x(&gamepads, gamepad_entity),
+            get_left_stick_y(&gamepads, gamepad_entity),
+        );
+        let is_stick_navigating = stick.abs().max_element() > STICK_NAVIGATION_THRESHOLD;
+        if is_stick_navigating && !*was_stick_navigating && direction == IVec2::ZERO {
+            direction = if stick.x.abs() > stick.y.abs() {
+                IVec2::new(stick.x.signum() as i32, 0)
+            } else {
+                IVec2::new(0, -stick.y.signum() as i32)
+            };
+        }
+        *was_stick_navigating = is_stick_navigating;
+    }
+
+    // Keyboard navigation (fallback if no gamepad direction)
+    if direction == IVec2::ZERO {
+        for (keys, step) in [
+            ([KeyCode::ArrowLeft, KeyCode::KeyA], IVec2::NEG_X),
+            ([KeyCode::ArrowRight, KeyCode::KeyD], IVec2::X),
+            ([KeyCode::ArrowUp, KeyCode::KeyW], IVec2::NEG_Y),
+            ([KeyCode::ArrowDown, KeyCode::KeyS], IVec2::Y),
+        ] {
+            if input.any_just_pressed(keys) {
+                direction = step;
+            }
+        }
+    }
+
+    if direction != IVec2::ZERO {
+        let offset = direction.x + direction.y * SLOT_COLUMNS as i32;
+        let new_index = (selected.index as i32 + offset).rem_euclid(selected.total as i32);
+        selected.index = new_index as usize;
+    }
+}
+
+/// Raccourcis clavier et manette des actions
+fn inventory_shortcuts(
+    input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState>,
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut action_events: EventWriter<InventoryActionEvent>,
+) {
+    let gamepad_pressed = |button| {
+        gamepad_state
+            .active_gamepad
+            .is_some_and(|gamepad_entity| is_button_just_pressed(&gamepads, gamepad_entity, button))
+    };
+
+    if gamepad_pressed(GamepadButton::South)
+        || input.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+    {
+        action_events.send(InventoryActionEvent(InventoryAction::Use));
+    }
+    if gamepad_pressed(GamepadButton::West) || input.just_pressed(KeyCode::KeyX) {
+        action_events.send(InventoryActionEvent(InventoryAction::Drop));
+    }
+    if action_state.just_pressed(Action::Inventory)
+        || gamepad_pressed(GamepadButton::East)
+        || input.just_pressed(KeyCode::Escape)
+    {
+        action_events.send(InventoryActionEvent(InventoryAction::Close));
+    }
+}
+
+/// Gestion de la souris sur les cases (le survol met à jour la sélection)
+fn slot_interactions(
+    interaction_query: Query<(&Interaction, &InventorySlotIndex), (Changed<Interaction>, With<Button>)>,
+    mut selected: ResMut<SelectedInventorySlot>,
+) {
+    for (interaction, slot_index) in &interaction_query {
+        if *interaction != Interaction::None {
+            selected.index = slot_index.0;
+        }
+    }
+}
+
+/// Gestion des clics sur les boutons d'action
+fn action_button_interactions(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &InventoryAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut action_events: EventWriter<InventoryActionEvent>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                action_events.send(InventoryActionEvent(*action));
+            }
+            Interaction::Hovered => *color = HOVERED_SLOT.into(),
+            Interaction::None => *color = NORMAL_SLOT.into(),
+        }
+    }
+}
+
+/// Mise à jour visuelle des cases selon la sélection
+fn update_slot_visuals(
+    selected: Res<SelectedInventorySlot>,
+    mut slot_query: Query<(&InventorySlotIndex, &Interaction, &mut BackgroundColor)>,
+) {
+    for (slot_index, interaction, mut color) in slot_query.iter_mut() {
+        *color = if slot_index.0 == selected.index {
+            SELECTED_SLOT.into()
+        } else if *interaction == Interaction::Hovered {
+            HOVERED_SLOT.into()
+        } else {
+            NORMAL_SLOT.into()
+        };
+    }
+}
+
+fn update_selected_item_text(
+    selected: Res<SelectedInventorySlot>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut text_query: Query<&mut Text, With<SelectedItemText>>,
+) {
+    let label = player_query
+        .get_single()
+        .ok()
+        .and_then(|inventory| inventory.stacks().get(selected.index))
+        .map(|stack| stack.item.label())
+        .unwrap_or_default();
+
+    for mut text in text_query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.to_string();
+        }
+    }
+}
+
+/// Exécute les actions de l'inventaire, qu'elles viennent du clavier, de la manette ou de la souris
+#[allow(clippy::too_many_arguments)]
+fn handle_inventory_actions(
+    mut commands: Commands,
+    mut action_events: EventReader<InventoryActionEvent>,
+    mut selected: ResMut<SelectedInventorySlot>,
+    mut player_query: Query<(Entity, &mut Inventory, &Transform, &Side, Option<&Parent>), With<Player>>,
+    screen_query: Query<Entity, With<InventoryScreen>>,
+    mut item_used_events: EventWriter<ItemUsed>,
+    item_effects: Res<ItemEffects>,
+    item_effect_tables: Res<Assets<ItemEffectTable>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    action_map: Res<ActionMap>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let Ok((player, mut inventory, transform, side, parent)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let ldtk_project = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle));
+
+    let mut content_changed = false;
+
+    for InventoryActionEvent(action) in action_events.read() {
+        let selected_item: Option<Item> = inventory
+            .stacks()
+            .get(selected.index)
+            .map(|stack| stack.item);
+
+        match (action, selected_item) {
+            (InventoryAction::Close, _) => next_state.set(GameState::InGame),
+            (InventoryAction::Use, Some(item)) => {
+                // Les objets non consommables agissent tant qu'ils sont portés
+                if !item_effects.is_consumable(&item_effect_tables, item) {
+                    info!("{} ne peut pas être utilisé", item.label());
+                } else if inventory.remove(item, 1) {
+                    content_changed = true;
+                    info!(
+                        "Objet utilisé : {} ({} restant(s))",
+                        item.label(),
+                        inventory.count(item)
+                    );
+                    item_used_events.send(ItemUsed {
+                        entity: player,
+                        item,
+                    });
+                }
+            }
+            (InventoryAction::Drop, Some(item)) if inventory.has(item) => {
+                let Some(sprite) = ldtk_project.and_then(|project| item_icon(project, item))
+                else {
+                    warn!("No icon found for item {:?}", item);
+                    continue;
+                };
+
+                if inventory.remove(item, 1) {
+                    content_changed = true;
+                    info!(
+                        "Objet jeté : {} ({} restant(s))",
+                        item.label(),
+                        inventory.count(item)
+                    );
+
+                    // Thrown in front of the player, out of reach of its collider
+                    let facing = match side {
+                        Side::Right => 1.,
+                        Side::Left => -1.,
+                    };
+                    let translation = transform.translation + Vec3::new(facing * 16., 4., 1.);
+                    let mut pickup = commands.spawn((
+                        item_pickup_bundle(item, sprite, translation, Vec2::new(facing * 120., 150.)),
+                        PickupDelay(Timer::from_seconds(DROP_PICKUP_DELAY, TimerMode::Once)),
+                    ));
+                    if let Some(parent) = parent {
+                        pickup.set_parent(parent.get());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Reconstruire l'écran avec le nouveau contenu
+    if content_changed {
+        for entity in screen_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_inventory_screen(
+            &mut commands,
+            &mut selected,
+            &inventory,
+            ldtk_project,
+            &action_map,
+        );
+    }
+}
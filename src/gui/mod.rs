@@ -1,3 +1,5 @@
 pub mod dialog;
+pub mod inventory_screen;
+pub mod pause_menu;
 pub mod player_interface;
 pub mod tooltip;
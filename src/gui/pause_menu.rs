@@ -0,0 +1,524 @@
+//! Pause menu, opened on top of the game with Escape or the Start button of the gamepad.
+//!
+//! Like the inventory, the pause freezes the physics, and the gameplay systems (timers,
+//! patrols, attacks) only run in `GameState::InGame`, so nothing moves while it is open.
+
+use bevy::prelude::*;
+
+use crate::core::save::{PendingCheckpoint, SaveSlots};
+use crate::gui::inventory_screen::{freeze_physics, resume_physics};
+use crate::input::{
+    get_left_stick_y, is_button_just_pressed, is_dpad_down_just_pressed, is_dpad_up_just_pressed,
+    GamepadState, STICK_NAVIGATION_THRESHOLD,
+};
+use crate::menu::{SettingsMenuState, StickNavigationState};
+use crate::GameState;
+
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+const SELECTED_BUTTON: Color = Color::srgb(0.3, 0.5, 0.8);
+const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
+
+/// Marqueur pour les entités du menu pause
+#[derive(Component)]
+pub struct PauseMenu;
+
+/// Action associée à un bouton du menu pause
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    Resume,
+    Options,
+    /// Reprendre depuis la sauvegarde de l'emplacement actif
+    RestartFromCheckpoint,
+    QuitToMenu,
+}
+
+/// Événement envoyé quand un bouton du menu pause est validé
+#[derive(Event)]
+pub struct PauseActionEvent(pub PauseAction);
+
+/// Index du bouton dans le menu pause (pour la navigation)
+#[derive(Component)]
+pub struct PauseButtonIndex(pub usize);
+
+/// Ressource pour suivre le bouton actuellement sélectionné
+#[derive(Resource, Default)]
+pub struct SelectedPauseButton {
+    pub index: usize,
+    pub total: usize,
+}
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PauseActionEvent>()
+            .init_resource::<SelectedPauseButton>()
+            .add_systems(Update, open_pause_menu.run_if(in_state(GameState::InGame)))
+            .add_systems(
+                OnEnter(GameState::Paused),
+                (freeze_physics, setup_pause_menu),
+            )
+            .add_systems(
+                OnExit(GameState::Paused),
+                (resume_physics, cleanup_pause_menu),
+            )
+            .add_systems(
+                Update,
+                (
+                    pause_navigation,
+                    pause_shortcuts,
+                    pause_button_interactions,
+                    update_pause_button_visuals,
+                    handle_pause_actions,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Paused))
+                    .run_if(in_state(SettingsMenuState::Closed)),
+            );
+    }
+}
+
+/// Escape or the Start button of the active gamepad
+fn pause_just_pressed(
+    input: &ButtonInput<KeyCode>,
+    gamepad_state: &GamepadState,
+    gamepads: &Query<&Gamepad>,
+) -> bool {
+    input.just_pressed(KeyCode::Escape)
+        || gamepad_state.active_gamepad.is_some_and(|gamepad_entity| {
+            is_button_just_pressed(gamepads, gamepad_entity, GamepadButton::Start)
+        })
+}
+
+fn open_pause_menu(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if pause_just_pressed(&input, &gamepad_state, &gamepads) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+fn setup_pause_menu(
+    mut commands: Commands,
+    mut selected: ResMut<SelectedPauseButton>,
+    save_slots: Res<SaveSlots>,
+) {
+    let mut buttons = vec![
+        ("Reprendre", PauseAction::Resume),
+        ("Options", PauseAction::Options),
+    ];
+    // Sans sauvegarde il n'y a pas de point de reprise
+    if save_slots.active_save().is_some() {
+        buttons.push((
+            "Recommencer au point de sauvegarde",
+            PauseAction::RestartFromCheckpoint,
+        ));
+    }
+    buttons.push(("Quitter vers le menu", PauseAction::QuitToMenu));
+
+    selected.index = 0;
+    selected.total = buttons.len();
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Pause"),
+                TextFont {
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+
+            for (index, (label, action)) in buttons.into_iter().enumerate() {
+                let color = if index == selected.index {
+                    SELECTED_BUTTON
+                } else {
+                    NORMAL_BUTTON
+                };
+
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(420.0),
+                            height: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                        BorderRadius::all(Val::Px(8.0)),
+                        action,
+                        PauseButtonIndex(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(label),
+                            TextFont {
+                                font_size: 28.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    });
+            }
+
+            parent.spawn((
+                Text::new("Échap / Start : Reprendre"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.5, 0.5, 0.5)),
+                Node {
+                    margin: UiRect::top(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn cleanup_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Navigation clavier et manette dans le menu pause
+fn pause_navigation(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut selected: ResMut<SelectedPauseButton>,
+    mut stick_nav: ResMut<StickNavigationState>,
+) {
+    let mut direction: i32 = 0;
+    let mut is_stick_navigating = false;
+
+    if let Some(gamepad_entity) = gamepad_state.active_gamepad {
+        if is_dpad_up_just_pressed(&gamepads, gamepad_entity) {
+            direction = -1;
+        }
+        if is_dpad_down_just_pressed(&gamepads, gamepad_entity) {
+            direction = 1;
+        }
+        let stick_y = get_left_stick_y(&gamepads, gamepad_entity);
+        if stick_y.abs() > STICK_NAVIGATION_THRESHOLD {
+            is_stick_navigating = true;
+            if !stick_nav.was_navigating && direction == 0 {
+                direction = if stick_y > 0.0 { -1 } else { 1 };
+            }
+        }
+    }
+    stick_nav.was_navigating = is_stick_navigating;
+
+    if input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        direction = -1;
+    }
+    if input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        direction = 1;
+    }
+
+    if direction != 0 && selected.total > 0 {
+        let new_index = (selected.index as i32 + direction).rem_euclid(selected.total as i32);
+        selected.index = new_index as usize;
+    }
+}
+
+/// Validation avec Entrée, Espace ou A ; Échap, Start ou B reprennent la partie
+fn pause_shortcuts(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    selected: Res<SelectedPauseButton>,
+    button_query: Query<(&PauseButtonIndex, &PauseAction)>,
+    mut action_events: EventWriter<PauseActionEvent>,
+) {
+    let gamepad_pressed = |button| {
+        gamepad_state
+            .active_gamepad
+            .is_some_and(|gamepad_entity| is_button_just_pressed(&gamepads, gamepad_entity, button))
+    };
+
+    if gamepad_pressed(GamepadButton::South)
+        || input.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+    {
+        if let Some((_, action)) = button_query
+            .iter()
+            .find(|(button_index, _)| button_index.0 == selected.index)
+        {
+            action_events.send(PauseActionEvent(*action));
+        }
+    }
+    if pause_just_pressed(&input, &gamepad_state, &gamepads) || gamepad_pressed(GamepadButton::East)
+    {
+        action_events.send(PauseActionEvent(PauseAction::Resume));
+    }
+}
+
+/// Gestion de la souris sur les boutons (le survol met à jour la sélection)
+fn pause_button_interactions(
+    interaction_query: Query<
+        (&Interaction, &PauseButtonIndex, &PauseAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut selected: ResMut<SelectedPauseButton>,
+    mut action_events: EventWriter<PauseActionEvent>,
+) {
+    for (interaction, button_index, action) in &interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                action_events.send(PauseActionEvent(*action));
+            }
+            Interaction::Hovered => selected.index = button_index.0,
+            Interaction::None => {}
+        }
+    }
+}
+
+fn update_pause_button_visuals(
+    selected: Res<SelectedPauseButton>,
+    mut button_query: Query<(&PauseButtonIndex, &Interaction, &mut BackgroundColor)>,
+) {
+    for (button_index, interaction, mut color) in button_query.iter_mut() {
+        let new_color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON,
+            Interaction::Hovered => HOVERED_BUTTON,
+            Interaction::None if button_index.0 == selected.index => SELECTED_BUTTON,
+            Interaction::None => NORMAL_BUTTON,
+        };
+        color.set_if_neq(new_color.into());
+    }
+}
+
+/// Exécute les actions du menu pause, qu'elles viennent du clavier, de la manette ou de la souris
+fn handle_pause_actions(
+    mut action_events: EventReader<PauseActionEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut settings_state: ResMut<NextState<SettingsMenuState>>,
+    save_slots: Res<SaveSlots>,
+    mut pending_checkpoint: ResMut<PendingCheckpoint>,
+) {
+    // Une seule action par image : deux entrées simultanées ne doivent pas s'enchaîner
+    let Some(PauseActionEvent(action)) = action_events.read().last() else {
+        return;
+    };
+
+    match action {
+        PauseAction::Resume => next_state.set(GameState::InGame),
+        PauseAction::Options => settings_state.set(SettingsMenuState::Open),
+        PauseAction::RestartFromCheckpoint => {
+            if let Some(save) = save_slots.active_save() {
+                info!("Retour au dernier point de sauvegarde");
+                pending_checkpoint.0 = Some(save.clone());
+                // Comme après un game over, la session repart d'un monde neuf
+                next_state.set(GameState::Restarting);
+            }
+        }
+        PauseAction::QuitToMenu => {
+            info!("Retour au menu principal");
+            next_state.set(GameState::Menu);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy::window::PrimaryWindow;
+    use bevy_ecs_ldtk::prelude::*;
+
+    use crate::config::{AudioConfig, DisplayConfig};
+    use crate::core::camera::camera_fit_inside_current_level;
+    use crate::core::inventory::Inventory;
+    use crate::core::save::{SaveData, SaveSlots, SAVE_VERSION};
+    use crate::engine::damage::Invincibility;
+    use crate::entities::enemy::Enemy;
+    use crate::menu::settings::{setup_settings_menu, SelectedBindingRow, SettingsEntity};
+    use crate::menu::SettingsMenuState;
+    use crate::testing::TestGame;
+    use crate::world::objects::WorldObjectStates;
+    use crate::GameState;
+
+    #[test]
+    fn pause_freezes_the_player_and_its_timers() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+
+        game.app
+            .world_mut()
+            .entity_mut(player)
+            .insert(Invincibility::new(1.0));
+        game.press_key(KeyCode::KeyD);
+        game.step(5);
+        game.tap_key(KeyCode::Escape);
+        game.step(1);
+        assert_eq!(game.state(), GameState::Paused);
+
+        let position = game.position(player);
+        let elapsed = game.get::<Invincibility>(player).unwrap().timer.elapsed();
+        game.step(30);
+        assert_eq!(game.position(player), position);
+        assert_eq!(
+            game.get::<Invincibility>(player).unwrap().timer.elapsed(),
+            elapsed
+        );
+
+        game.tap_key(KeyCode::Escape);
+        game.step(10);
+        assert_eq!(game.state(), GameState::InGame);
+        assert!(game.position(player).x > position.x);
+    }
+
+    #[test]
+    fn start_button_opens_the_pause_menu() {
+        let mut game = TestGame::new();
+        let gamepad = game.connect_gamepad();
+
+        game.tap_button(gamepad, GamepadButton::Start);
+        game.step(1);
+        assert_eq!(game.state(), GameState::Paused);
+
+        // B resumes as well
+        game.tap_button(gamepad, GamepadButton::East);
+        game.step(1);
+        assert_eq!(game.state(), GameState::InGame);
+    }
+
+    #[test]
+    fn options_open_over_the_paused_game() {
+        let mut game = TestGame::new();
+        game.settle();
+        // The game view is fitted to the window while the settings draw their own camera
+        game.app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow));
+        game.app
+            .init_resource::<AudioConfig>()
+            .init_resource::<DisplayConfig>()
+            .init_resource::<SelectedBindingRow>()
+            .add_systems(Update, camera_fit_inside_current_level)
+            .add_systems(OnEnter(SettingsMenuState::Open), setup_settings_menu);
+
+        game.tap_key(KeyCode::Escape);
+        game.step(1);
+        game.tap_key(KeyCode::ArrowDown);
+        game.tap_key(KeyCode::Enter);
+        game.step(5);
+
+        assert_eq!(game.state(), GameState::Paused);
+        assert_eq!(
+            *game
+                .app
+                .world()
+                .resource::<State<SettingsMenuState>>()
+                .get(),
+            SettingsMenuState::Open
+        );
+        assert!(game.find::<SettingsEntity>().is_some());
+    }
+
+    #[test]
+    fn quit_to_menu_from_the_pause_menu() {
+        let mut game = TestGame::new();
+        game.tap_key(KeyCode::Escape);
+        game.step(1);
+
+        // Reprendre, Options, Quitter (no checkpoint in the empty save slots)
+        game.tap_key(KeyCode::ArrowUp);
+        game.tap_key(KeyCode::Enter);
+        game.step(1);
+        assert_eq!(game.state(), GameState::Menu);
+    }
+
+    #[test]
+    fn restart_from_checkpoint_starts_from_a_fresh_world() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let spawn_position = game.position(player);
+        let mut saved_stats = game.stats(player);
+        saved_stats.life -= 1;
+        let level_iid = game
+            .app
+            .world_mut()
+            .query::<&LevelIid>()
+            .single(game.app.world())
+            .to_string();
+        game.app.insert_resource(SaveSlots {
+            active: 0,
+            saves: vec![Some(SaveData {
+                version: SAVE_VERSION,
+                saved_at: 0,
+                level_iid,
+                position: spawn_position.to_array(),
+                stats: saved_stats.clone(),
+                inventory: Inventory::default(),
+            })],
+        });
+
+        // Leave a trace of the session: a killed mob, an opened chest and a running effect
+        let mob = game.find::<Enemy>().unwrap();
+        game.app.world_mut().entity_mut(mob).despawn_recursive();
+        game.app
+            .world_mut()
+            .resource_mut::<WorldObjectStates>()
+            .opened_chests
+            .insert("chest".to_string());
+        game.app
+            .world_mut()
+            .entity_mut(player)
+            .insert(Invincibility::new(10.0));
+        game.press_key(KeyCode::KeyD);
+        game.step(20);
+        game.release_key(KeyCode::KeyD);
+
+        // Reprendre, Options, Recommencer, Quitter
+        game.tap_key(KeyCode::Escape);
+        game.step(1);
+        game.tap_key(KeyCode::ArrowUp);
+        game.tap_key(KeyCode::ArrowUp);
+        game.tap_key(KeyCode::Enter);
+        game.step(1);
+        assert!(game.try_player().is_none());
+        game.step(1);
+        assert_eq!(game.state(), GameState::InGame);
+        game.start_session();
+        game.settle();
+
+        let player = game.player();
+        assert!(game.get::<Invincibility>(player).is_none());
+        assert_eq!(game.stats(player), saved_stats);
+        assert_eq!(game.position(player), spawn_position);
+        assert!(game.find::<Enemy>().is_some());
+        assert!(game
+            .app
+            .world()
+            .resource::<WorldObjectStates>()
+            .opened_chests
+            .is_empty());
+    }
+}
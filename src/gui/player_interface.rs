@@ -5,7 +5,7 @@ use bevy::{
 
 use crate::{
     entities::{player::Player, stats::Stats},
-    GameState,
+    GameState, InGameplay,
 };
 
 #[derive(Component)]
@@ -14,24 +14,22 @@ struct Bar;
 #[derive(Component)]
 struct LifeBarText;
 
-#[derive(Resource)]
-struct PlayerInterface {
-    container: Entity,
-}
-
 fn setup(mut commands: Commands) {
-    let container = commands
-        .spawn(Node {
-            // center button
-            width: Val::Percent(100.),
-            height: Val::Percent(100.),
-            top: Val::Px(0.),
-            left: Val::Px(0.),
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::SpaceBetween,
-            align_items: AlignItems::Center,
-            ..default()
-        })
+    commands
+        .spawn((
+            Node {
+                // center button
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                top: Val::Px(0.),
+                left: Val::Px(0.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            StateScoped(InGameplay),
+        ))
         .with_children(|parent| {
             parent
                 .spawn((
@@ -73,10 +71,7 @@ fn setup(mut commands: Commands) {
                         LifeBarText,
                     ));
                 });
-        })
-        .id();
-
-    commands.insert_resource(PlayerInterface { container });
+        });
 }
 
 fn menu(
@@ -90,18 +85,11 @@ fn menu(
     }
 }
 
-fn cleanup(mut commands: Commands, player_interface: Res<PlayerInterface>) {
-    commands
-        .entity(player_interface.container)
-        .despawn_recursive();
-}
-
 pub struct PlayerInterfacePlugin;
 
 impl Plugin for PlayerInterfacePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup)
-            .add_systems(Update, menu.run_if(in_state(GameState::InGame)))
-            .add_systems(OnExit(GameState::InGame), cleanup);
+        app.add_systems(OnEnter(InGameplay), setup)
+            .add_systems(Update, menu.run_if(in_state(GameState::InGame)));
     }
 }
@@ -5,7 +5,7 @@
 
 use bevy::prelude::*;
 
-use crate::GameState;
+use crate::{GameState, InGameplay};
 
 /// Component to attach a tooltip to an entity.
 /// The tooltip will be displayed above the entity in world space.
@@ -19,31 +19,6 @@ pub struct Tooltip {
     pub visible: bool,
 }
 
-impl Tooltip {
-    /// Create a new tooltip with the given text
-    pub fn new(text: impl Into<String>) -> Self {
-        Self {
-            text: text.into(),
-            offset_y: 30.0,
-            visible: true,
-        }
-    }
-
-    /// Create a new tooltip with custom offset
-    pub fn with_offset(text: impl Into<String>, offset_y: f32) -> Self {
-        Self {
-            text: text.into(),
-            offset_y,
-            visible: true,
-        }
-    }
-
-    /// Set the visibility of the tooltip
-    pub fn set_visible(&mut self, visible: bool) {
-        self.visible = visible;
-    }
-}
-
 impl Default for Tooltip {
     fn default() -> Self {
         Self {
@@ -68,11 +43,16 @@ impl Plugin for TooltipPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (spawn_tooltip_displays, update_tooltip_positions, update_tooltip_visibility)
+            (
+                cleanup_orphaned_tooltips,
+                spawn_tooltip_displays,
+                update_tooltip_positions,
+                update_tooltip_visibility,
+            )
                 .chain()
                 .run_if(in_state(GameState::InGame)),
         )
-        .add_systems(OnExit(GameState::InGame), cleanup_tooltips);
+        .add_systems(OnExit(InGameplay), cleanup_tooltips);
     }
 }
 
@@ -141,8 +121,8 @@ fn cleanup_tooltips(
     }
 }
 
-/// System to handle despawned target entities (can be added for runtime cleanup)
-pub fn cleanup_orphaned_tooltips(
+/// System to despawn the tooltips whose target entity is gone (a level was unloaded)
+fn cleanup_orphaned_tooltips(
     mut commands: Commands,
     entity_query: Query<Entity, With<Tooltip>>,
     tooltip_query: Query<(Entity, &TooltipDisplay)>,
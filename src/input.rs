@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 
+/// Gameplay actions bound to the keyboard, the mouse and the gamepads
+pub mod actions;
+
+use actions::ActionPlugin;
+
 /// Resource that tracks whether a gamepad is currently connected
 #[derive(Resource, Default)]
 pub struct GamepadState {
@@ -16,9 +21,13 @@ pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GamepadState>()
+        app.add_plugins(ActionPlugin)
+            .init_resource::<GamepadState>()
             .add_systems(Update, gamepad_connection_system)
-            .add_systems(Update, mobile_gamepad_error_system.run_if(is_mobile_platform));
+            .add_systems(
+                Update,
+                mobile_gamepad_error_system.run_if(is_mobile_platform),
+            );
     }
 }
 
@@ -189,19 +198,6 @@ pub fn is_button_just_pressed(
     }
 }
 
-/// Check if a gamepad button is pressed
-pub fn is_button_pressed(
-    gamepads: &Query<&Gamepad>,
-    gamepad_entity: Entity,
-    button: GamepadButton,
-) -> bool {
-    if let Ok(gamepad) = gamepads.get(gamepad_entity) {
-        gamepad.pressed(button)
-    } else {
-        false
-    }
-}
-
 /// Check if D-pad up was just pressed
 pub fn is_dpad_up_just_pressed(gamepads: &Query<&Gamepad>, gamepad_entity: Entity) -> bool {
     is_button_just_pressed(gamepads, gamepad_entity, GamepadButton::DPadUp)
@@ -211,13 +207,3 @@ pub fn is_dpad_up_just_pressed(gamepads: &Query<&Gamepad>, gamepad_entity: Entit
 pub fn is_dpad_down_just_pressed(gamepads: &Query<&Gamepad>, gamepad_entity: Entity) -> bool {
     is_button_just_pressed(gamepads, gamepad_entity, GamepadButton::DPadDown)
 }
-
-/// Check if D-pad left is pressed
-pub fn is_dpad_left_pressed(gamepads: &Query<&Gamepad>, gamepad_entity: Entity) -> bool {
-    is_button_pressed(gamepads, gamepad_entity, GamepadButton::DPadLeft)
-}
-
-/// Check if D-pad right is pressed
-pub fn is_dpad_right_pressed(gamepads: &Query<&Gamepad>, gamepad_entity: Entity) -> bool {
-    is_button_pressed(gamepads, gamepad_entity, GamepadButton::DPadRight)
-}
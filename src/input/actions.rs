@@ -0,0 +1,328 @@
+//! Action map: the gameplay systems read abstract actions (`Action::Jump`, `Action::Attack`...)
+//! instead of raw devices.
+//!
+//! Each action has several bindings across the keyboard, the mouse and the gamepads, all
+//! read at the same time: picking up the gamepad in the middle of a jump just works.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::STICK_DEADZONE;
+
+/// Gameplay actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Jump,
+    Attack,
+    Interact,
+    Inventory,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Jump,
+        Action::Attack,
+        Action::Interact,
+        Action::Inventory,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Gauche",
+            Action::MoveRight => "Droite",
+            Action::MoveUp => "Haut",
+            Action::MoveDown => "Bas",
+            Action::Jump => "Sauter",
+            Action::Attack => "Attaquer",
+            Action::Interact => "Interagir",
+            Action::Inventory => "Inventaire",
+        }
+    }
+}
+
+/// Physical input triggering an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    /// One direction of a gamepad axis (`positive` for right/up)
+    GamepadAxis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+/// Bindings of every action
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ActionMap {
+    bindings: HashMap<Action, Vec<InputBinding>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        let key = InputBinding::Key;
+        let button = InputBinding::GamepadButton;
+        let stick = |axis, positive| InputBinding::GamepadAxis { axis, positive };
+
+        let bindings = HashMap::from_iter([
+            (
+                Action::MoveLeft,
+                vec![
+                    key(KeyCode::KeyA),
+                    button(GamepadButton::DPadLeft),
+                    stick(GamepadAxis::LeftStickX, false),
+                ],
+            ),
+            (
+                Action::MoveRight,
+                vec![
+                    key(KeyCode::KeyD),
+                    button(GamepadButton::DPadRight),
+                    stick(GamepadAxis::LeftStickX, true),
+                ],
+            ),
+            (
+                Action::MoveUp,
+                vec![
+                    key(KeyCode::KeyW),
+                    button(GamepadButton::DPadUp),
+                    stick(GamepadAxis::LeftStickY, true),
+                ],
+            ),
+            (
+                Action::MoveDown,
+                vec![
+                    key(KeyCode::KeyS),
+                    button(GamepadButton::DPadDown),
+                    stick(GamepadAxis::LeftStickY, false),
+                ],
+            ),
+            (
+                Action::Jump,
+                vec![key(KeyCode::Space), button(GamepadButton::South)],
+            ),
+            (
+                Action::Attack,
+                vec![
+                    key(KeyCode::KeyK),
+                    InputBinding::Mouse(MouseButton::Left),
+                    button(GamepadButton::West),
+                ],
+            ),
+            (
+                Action::Interact,
+                vec![key(KeyCode::KeyO), button(GamepadButton::East)],
+            ),
+            (
+                Action::Inventory,
+                vec![key(KeyCode::KeyI), button(GamepadButton::North)],
+            ),
+        ]);
+
+        Self { bindings }
+    }
+}
+
+impl ActionMap {
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Keyboard key of an action, as shown (and remapped) in the settings
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.bindings(action)
+            .iter()
+            .find_map(|binding| match binding {
+                InputBinding::Key(key) => Some(*key),
+                _ => None,
+            })
+    }
+
+    /// Replace the keyboard key of an action, keeping its other bindings
+    pub fn set_key(&mut self, action: Action, key: KeyCode) {
+        let bindings = self.bindings.entry(action).or_default();
+        match bindings
+            .iter_mut()
+            .find(|binding| matches!(binding, InputBinding::Key(_)))
+        {
+            Some(binding) => *binding = InputBinding::Key(key),
+            None => bindings.insert(0, InputBinding::Key(key)),
+        }
+    }
+
+    /// Gamepad button of an action, as shown (and remapped) in the settings
+    pub fn gamepad_button(&self, action: Action) -> Option<GamepadButton> {
+        self.bindings(action)
+            .iter()
+            .find_map(|binding| match binding {
+                InputBinding::GamepadButton(button) => Some(*button),
+                _ => None,
+            })
+    }
+
+    /// Replace the gamepad button of an action, keeping its other bindings (sticks included)
+    pub fn set_gamepad_button(&mut self, action: Action, button: GamepadButton) {
+        let bindings = self.bindings.entry(action).or_default();
+        match bindings
+            .iter_mut()
+            .find(|binding| matches!(binding, InputBinding::GamepadButton(_)))
+        {
+            Some(binding) => *binding = InputBinding::GamepadButton(button),
+            None => self.add_binding(action, InputBinding::GamepadButton(button)),
+        }
+    }
+
+    /// Add a binding to an action, unless it is already bound
+    pub fn add_binding(&mut self, action: Action, binding: InputBinding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Actions triggered by a binding, in the order of `Action::ALL`
+    pub fn actions_bound_to(&self, binding: InputBinding) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|action| self.bindings(*action).contains(&binding))
+            .collect()
+    }
+
+    /// Bindings shared by several actions, with the actions sharing them
+    pub fn conflicts(&self) -> Vec<(InputBinding, Vec<Action>)> {
+        let mut conflicts: Vec<(InputBinding, Vec<Action>)> = Vec::new();
+        for action in Action::ALL {
+            for binding in self.bindings(action) {
+                if conflicts.iter().any(|(shared, _)| shared == binding) {
+                    continue;
+                }
+                let actions = self.actions_bound_to(*binding);
+                if actions.len() > 1 {
+                    conflicts.push((*binding, actions));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ActionData {
+    /// Strength of the action, from 0 to 1 (analog sticks give intermediate values)
+    value: f32,
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+}
+
+/// State of every action for the current frame, updated in `PreUpdate`
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    actions: HashMap<Action, ActionData>,
+}
+
+impl ActionState {
+    fn data(&self, action: Action) -> ActionData {
+        self.actions.get(&action).copied().unwrap_or_default()
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.data(action).pressed
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.data(action).just_pressed
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.data(action).just_released
+    }
+
+    pub fn value(&self, action: Action) -> f32 {
+        self.data(action).value
+    }
+
+    /// Axis from two opposite actions, from -1 to 1
+    pub fn axis(&self, negative: Action, positive: Action) -> f32 {
+        self.value(positive) - self.value(negative)
+    }
+
+    /// Update an action from the strength of its bindings
+    fn set(&mut self, action: Action, value: f32) {
+        let data = self.actions.entry(action).or_default();
+        let pressed = value > 0.0;
+        data.just_pressed = pressed && !data.pressed;
+        data.just_released = !pressed && data.pressed;
+        data.pressed = pressed;
+        data.value = value;
+    }
+}
+
+/// Strength of a binding: 1 for pressed buttons, the stick deflection past the deadzone
+/// for axes, the strongest of all connected gamepads
+fn binding_value(
+    binding: &InputBinding,
+    keyboard: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepads: &Query<&Gamepad>,
+) -> f32 {
+    let pressed = |pressed: bool| if pressed { 1.0 } else { 0.0 };
+
+    match binding {
+        InputBinding::Key(key) => pressed(keyboard.pressed(*key)),
+        InputBinding::Mouse(button) => pressed(mouse.pressed(*button)),
+        InputBinding::GamepadButton(button) => {
+            pressed(gamepads.iter().any(|gamepad| gamepad.pressed(*button)))
+        }
+        InputBinding::GamepadAxis { axis, positive } => gamepads
+            .iter()
+            .map(|gamepad| {
+                let value = gamepad.get(*axis).unwrap_or(0.0);
+                let value = if *positive { value } else { -value };
+                if value > STICK_DEADZONE {
+                    value.min(1.0)
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f32::max),
+    }
+}
+
+/// Système qui met à jour l'état des actions à partir du clavier, de la souris et des manettes
+pub fn update_action_state(
+    action_map: Res<ActionMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut action_state: ResMut<ActionState>,
+) {
+    for action in Action::ALL {
+        let value = action_map
+            .bindings(action)
+            .iter()
+            .map(|binding| binding_value(binding, &keyboard, &mouse, &gamepads))
+            .fold(0.0, f32::max);
+        action_state.set(action, value);
+    }
+}
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionMap>()
+            .init_resource::<ActionState>()
+            .add_systems(PreUpdate, update_action_state.after(InputSystem));
+    }
+}
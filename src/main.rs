@@ -3,17 +3,17 @@
 #![allow(clippy::type_complexity)]
 use bevy::{prelude::*, window::WindowResolution};
 use bevy_ecs_ldtk::prelude::*;
+#[cfg(not(feature = "hot_reload"))]
 use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
 
-use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier2d::prelude::*;
 use engine::damage::DamagePlugin;
 
 use crate::combat::CombatPlugin;
+use crate::physics::one_way_platforms::OneWayPlatformHooks;
 
-pub const WINDOW_HEIGHT: usize = 720;
-pub const WINDOW_WIDTH: usize = 1080;
-
+/// Music and sound effects
+mod audio;
 mod combat;
 /// Configuration module (key bindings, volume, etc.)
 mod config;
@@ -27,6 +27,9 @@ mod input;
 mod menu;
 /// Physics-related modules (colliders, climbing, ground detection, walls)
 mod physics;
+/// Headless simulation harness for the gameplay tests
+#[cfg(test)]
+mod testing;
 /// World objects (chests, doors, etc.)
 mod world;
 
@@ -35,61 +38,98 @@ pub enum GameState {
     #[default]
     Menu,
     InGame,
-    _Inventory,
+    Inventory,
+    /// Menu pause par-dessus le jeu
+    Paused,
     GameOver,
+    /// Fin de la session en cours, avant d'en relancer une depuis le dernier point de sauvegarde
+    Restarting,
+}
+
+/// Active while a game session is running, including the overlays drawn on top of it.
+///
+/// The level, the HUD and the dialogs are set up and torn down on this state, so that
+/// opening the inventory or the pause menu does not reload the world.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct InGameplay;
+
+impl ComputedStates for InGameplay {
+    type SourceStates = GameState;
+
+    fn compute(sources: GameState) -> Option<Self> {
+        matches!(
+            sources,
+            GameState::InGame | GameState::Inventory | GameState::Paused
+        )
+        .then_some(InGameplay)
+    }
 }
 
 fn main() {
-    App::new()
-        .add_plugins(EmbeddedAssetPlugin {
-            mode: PluginMode::ReplaceDefault,
-        })
-        .add_plugins(
-            DefaultPlugins
-                .set(ImagePlugin::default_nearest())
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "The Seventh".to_string(),
-                        resolution: WindowResolution::new(
-                            WINDOW_WIDTH as f32,
-                            WINDOW_HEIGHT as f32,
-                        ),
-                        resizable: false,
-                        ..default()
-                    }),
+    let default_resolution = config::DisplayConfig::default().resolution;
+
+    let mut app = App::new();
+    // With `hot_reload`, the assets are read from the disk and watched instead
+    #[cfg(not(feature = "hot_reload"))]
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    });
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(ImagePlugin::default_nearest())
+            .set(WindowPlugin {
+                // Resized to the display options once they are loaded
+                primary_window: Some(Window {
+                    title: "The Seventh".to_string(),
+                    resolution: WindowResolution::new(
+                        default_resolution.x as f32,
+                        default_resolution.y as f32,
+                    ),
                     ..default()
                 }),
-        )
-        .add_plugins(config::ConfigPlugin)
-        .add_plugins(input::InputPlugin)
-        .init_state::<GameState>()
-        .add_plugins((
-            LdtkPlugin,
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
-        ))
-        .insert_resource(LevelSelection::Uid(0))
-        .insert_resource(LdtkSettings {
-            level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
-                load_level_neighbors: true,
-            },
-            set_clear_color: SetClearColor::FromLevelBackground,
-            ..Default::default()
-        })
-        .add_plugins(core::game_flow::GameFlowPlugin)
-        .add_plugins(menu::MenuPlugin)
-        .add_plugins(physics::walls::WallPlugin)
-        .add_plugins(physics::ground_detection::GroundDetectionPlugin)
-        .add_plugins(physics::climbing::ClimbingPlugin)
-        .add_plugins(entities::player::PlayerPlugin)
-        .add_plugins(entities::enemy::EnemyPlugin)
-        // .add_systems(Update, core::inventory::dbg_print_inventory)
-        .add_systems(Update, core::camera::camera_fit_inside_current_level)
-        .add_plugins(world::objects::MiscObjectsPlugin)
-        // .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(DamagePlugin)
-        .add_plugins(CombatPlugin)
-        .add_plugins(core::game_over::GameOverPlugin)
-        .add_plugins(gui::dialog::DialogPlugin)
-        .add_plugins(gui::tooltip::TooltipPlugin)
-        .run();
+                ..default()
+            }),
+    )
+    .add_plugins(config::ConfigPlugin)
+    .add_plugins(core::display::DisplayPlugin)
+    .add_plugins(input::InputPlugin)
+    .init_state::<GameState>()
+    .add_computed_state::<InGameplay>()
+    .enable_state_scoped_entities::<InGameplay>()
+    .add_plugins((
+        LdtkPlugin,
+        RapierPhysicsPlugin::<OneWayPlatformHooks>::pixels_per_meter(100.0),
+    ))
+    .insert_resource(LdtkSettings {
+        level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
+            load_level_neighbors: true,
+        },
+        set_clear_color: SetClearColor::FromLevelBackground,
+        ..Default::default()
+    })
+    .add_plugins(core::game_flow::GameFlowPlugin)
+    .add_plugins(menu::MenuPlugin)
+    .add_plugins(physics::walls::WallPlugin)
+    .add_plugins(physics::one_way_platforms::OneWayPlatformPlugin)
+    .add_plugins(physics::ground_detection::GroundDetectionPlugin)
+    .add_plugins(physics::wall_detection::WallDetectionPlugin)
+    .add_plugins(physics::climbing::ClimbingPlugin)
+    .add_plugins(entities::player::PlayerPlugin)
+    .add_plugins(entities::enemy::EnemyPlugin)
+    .add_plugins(core::inventory::InventoryPlugin)
+    .add_plugins(core::item_effects::ItemEffectsPlugin)
+    .add_systems(Update, core::camera::camera_fit_inside_current_level)
+    .add_plugins(world::objects::MiscObjectsPlugin)
+    // .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+    .add_plugins(DamagePlugin)
+    .add_plugins(CombatPlugin)
+    .add_plugins(core::game_over::GameOverPlugin)
+    .add_plugins(core::save::SavePlugin)
+    .add_plugins(gui::dialog::DialogPlugin)
+    .add_plugins(gui::tooltip::TooltipPlugin)
+    .add_plugins(gui::inventory_screen::InventoryScreenPlugin)
+    .add_plugins(gui::pause_menu::PauseMenuPlugin)
+    .add_plugins(audio::GameAudioPlugin)
+    .run();
 }
@@ -2,6 +2,7 @@ pub mod settings;
 
 use bevy::prelude::*;
 
+use crate::core::save::{PendingCheckpoint, SaveSlots};
 use crate::input::{
     get_left_stick_y, is_button_just_pressed, is_dpad_down_just_pressed,
     is_dpad_up_just_pressed, GamepadState, STICK_NAVIGATION_THRESHOLD,
@@ -13,17 +14,26 @@ use settings::*;
 #[derive(Component)]
 pub struct MenuEntity;
 
-/// Marqueur pour le bouton "Jouer"
+/// Marqueur pour le conteneur des boutons (reconstruit quand l'emplacement actif change)
 #[derive(Component)]
-pub struct PlayButton;
-
-/// Marqueur pour le bouton "Options"
-#[derive(Component)]
-pub struct OptionsButton;
+pub struct MenuRoot;
+
+/// Action associée à un bouton du menu
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Reprendre depuis la sauvegarde de l'emplacement actif
+    Continue,
+    /// Nouvelle partie
+    Play,
+    /// Changer d'emplacement de sauvegarde
+    SaveSlot,
+    Options,
+    Quit,
+}
 
-/// Marqueur pour le bouton "Quitter"
-#[derive(Component)]
-pub struct QuitButton;
+/// Événement envoyé quand un bouton du menu est validé
+#[derive(Event)]
+pub struct MenuActionEvent(pub MenuAction);
 
 /// Index du bouton dans le menu (pour la navigation)
 #[derive(Component)]
@@ -60,9 +70,11 @@ pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SelectedMenuButton>()
+        app.add_event::<MenuActionEvent>()
+            .init_resource::<SelectedMenuButton>()
             .init_resource::<StickNavigationState>()
-            .init_resource::<EditingKeyBinding>()
+            .init_resource::<EditingBinding>()
+            .init_resource::<SelectedBindingRow>()
             .init_state::<SettingsMenuState>()
             .add_systems(OnEnter(GameState::Menu), setup_menu)
             .add_systems(OnExit(GameState::Menu), cleanup_menu)
@@ -73,9 +85,8 @@ impl Plugin for MenuPlugin {
                     keyboard_selection,
                     button_system,
                     update_button_visuals,
-                    play_button_action,
-                    options_button_action,
-                    quit_button_action,
+                    menu_button_action,
+                    handle_menu_actions,
                 )
                     .run_if(in_state(GameState::Menu))
                     .run_if(in_state(SettingsMenuState::Closed)),
@@ -87,27 +98,70 @@ impl Plugin for MenuPlugin {
                 Update,
                 (
                     handle_volume_buttons,
-                    handle_volume_button_hover,
-                    handle_key_binding_buttons,
-                    update_key_binding_button_colors,
+                    handle_display_buttons,
+                    handle_option_button_hover,
+                    handle_binding_buttons,
+                    update_binding_button_colors,
                     capture_key_input,
-                    handle_back_button,
+                    // The captured button must not also navigate nor leave the menu
+                    (
+                        capture_gamepad_input,
+                        navigate_binding_rows,
+                        handle_back_button,
+                    )
+                        .chain(),
+                    update_binding_conflicts,
                     handle_back_button_hover,
                 )
-                    .run_if(in_state(GameState::Menu))
+                    // Opened from the main menu or from the pause menu
+                    .run_if(in_state(GameState::Menu).or(in_state(GameState::Paused)))
                     .run_if(in_state(SettingsMenuState::Open)),
             );
     }
 }
 
-fn setup_menu(mut commands: Commands, mut selected: ResMut<SelectedMenuButton>) {
+fn setup_menu(
+    mut commands: Commands,
+    mut selected: ResMut<SelectedMenuButton>,
+    save_slots: Res<SaveSlots>,
+) {
     // Réinitialiser la sélection
     selected.index = 0;
-    selected.total = 3; // Nombre de boutons (Jouer, Options, Quitter)
 
     // Caméra UI pour le menu
     commands.spawn((Camera2d, MenuEntity));
 
+    spawn_menu_root(&mut commands, &mut selected, &save_slots, None);
+}
+
+/// Construit le conteneur principal du menu (dépend de l'emplacement de sauvegarde actif)
+fn spawn_menu_root(
+    commands: &mut Commands,
+    selected: &mut SelectedMenuButton,
+    save_slots: &SaveSlots,
+    focus: Option<MenuAction>,
+) {
+    let slot_label = if save_slots.active_save().is_some() {
+        format!("Sauvegarde {}", save_slots.active + 1)
+    } else {
+        format!("Sauvegarde {} (vide)", save_slots.active + 1)
+    };
+
+    let mut buttons = Vec::new();
+    // "Continuer" n'est proposé que si l'emplacement actif contient une sauvegarde
+    if save_slots.active_save().is_some() {
+        buttons.push(("Continuer".to_string(), MenuAction::Continue));
+    }
+    buttons.push(("Jouer".to_string(), MenuAction::Play));
+    buttons.push((slot_label, MenuAction::SaveSlot));
+    buttons.push(("Options".to_string(), MenuAction::Options));
+    buttons.push(("Quitter".to_string(), MenuAction::Quit));
+
+    selected.total = buttons.len();
+    selected.index = focus
+        .and_then(|focus| buttons.iter().position(|(_, action)| *action == focus))
+        .unwrap_or(selected.index.min(selected.total - 1));
+
     // Container principal
     commands
         .spawn((
@@ -122,6 +176,7 @@ fn setup_menu(mut commands: Commands, mut selected: ResMut<SelectedMenuButton>)
             },
             BackgroundColor(Color::srgb(0.1, 0.1, 0.15)),
             MenuEntity,
+            MenuRoot,
         ))
         .with_children(|parent| {
             // Titre du jeu
@@ -138,86 +193,39 @@ fn setup_menu(mut commands: Commands, mut selected: ResMut<SelectedMenuButton>)
                 },
             ));
 
-            // Bouton Jouer (index 0)
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(200.0),
-                        height: Val::Px(65.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BackgroundColor(SELECTED_BUTTON), // Sélectionné par défaut
-                    BorderRadius::all(Val::Px(8.0)),
-                    PlayButton,
-                    MenuButtonIndex(0),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Jouer"),
-                        TextFont {
-                            font_size: 33.0,
-                            ..default()
-                        },
-                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    ));
-                });
-
-            // Bouton Options (index 1)
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(200.0),
-                        height: Val::Px(65.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BackgroundColor(NORMAL_BUTTON),
-                    BorderRadius::all(Val::Px(8.0)),
-                    OptionsButton,
-                    MenuButtonIndex(1),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Options"),
-                        TextFont {
-                            font_size: 33.0,
-                            ..default()
-                        },
-                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    ));
-                });
-
-            // Bouton Quitter (index 2)
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(200.0),
-                        height: Val::Px(65.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BackgroundColor(NORMAL_BUTTON),
-                    BorderRadius::all(Val::Px(8.0)),
-                    QuitButton,
-                    MenuButtonIndex(2),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Quitter"),
-                        TextFont {
-                            font_size: 33.0,
+            for (index, (label, action)) in buttons.into_iter().enumerate() {
+                let color = if index == selected.index {
+                    SELECTED_BUTTON
+                } else {
+                    NORMAL_BUTTON
+                };
+
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(320.0),
+                            height: Val::Px(65.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
                             ..default()
                         },
-                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    ));
-                });
+                        BackgroundColor(color),
+                        BorderRadius::all(Val::Px(8.0)),
+                        action,
+                        MenuButtonIndex(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(label),
+                            TextFont {
+                                font_size: 33.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    });
+            }
 
             // Instructions de navigation
             parent.spawn((
@@ -300,9 +308,8 @@ fn keyboard_selection(
     gamepad_state: Res<GamepadState>,
     gamepads: Query<&Gamepad>,
     selected: Res<SelectedMenuButton>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut settings_state: ResMut<NextState<SettingsMenuState>>,
-    mut exit: EventWriter<AppExit>,
+    button_query: Query<(&MenuButtonIndex, &MenuAction)>,
+    mut action_events: EventWriter<MenuActionEvent>,
 ) {
     // Check for selection input from gamepad (priority) or keyboard
     let select_pressed = if let Some(gamepad_entity) = gamepad_state.active_gamepad {
@@ -314,13 +321,11 @@ fn keyboard_selection(
         || input.just_pressed(KeyCode::Space);
 
     if select_pressed {
-        match selected.index {
-            0 => next_state.set(GameState::InGame), // Jouer
-            1 => settings_state.set(SettingsMenuState::Open), // Options
-            2 => {
-                exit.send(AppExit::Success); // Quitter
-            }
-            _ => {}
+        if let Some((_, action)) = button_query
+            .iter()
+            .find(|(button_index, _)| button_index.0 == selected.index)
+        {
+            action_events.send(MenuActionEvent(*action));
         }
     }
 }
@@ -377,35 +382,62 @@ fn button_system(
     }
 }
 
-fn play_button_action(
-    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PlayButton>)>,
-    mut next_state: ResMut<NextState<GameState>>,
+/// Gestion des clics souris sur les boutons du menu
+fn menu_button_action(
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<Button>)>,
+    mut action_events: EventWriter<MenuActionEvent>,
 ) {
-    for interaction in &interaction_query {
+    for (interaction, action) in &interaction_query {
         if *interaction == Interaction::Pressed {
-            next_state.set(GameState::InGame);
+            action_events.send(MenuActionEvent(*action));
         }
     }
 }
 
-fn options_button_action(
-    interaction_query: Query<&Interaction, (Changed<Interaction>, With<OptionsButton>)>,
+/// Exécute les actions du menu, qu'elles viennent du clavier, de la manette ou de la souris
+#[allow(clippy::too_many_arguments)]
+fn handle_menu_actions(
+    mut commands: Commands,
+    mut action_events: EventReader<MenuActionEvent>,
+    mut selected: ResMut<SelectedMenuButton>,
+    root_query: Query<Entity, With<MenuRoot>>,
+    mut next_state: ResMut<NextState<GameState>>,
     mut settings_state: ResMut<NextState<SettingsMenuState>>,
-) {
-    for interaction in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            settings_state.set(SettingsMenuState::Open);
-        }
-    }
-}
-
-fn quit_button_action(
-    interaction_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+    mut save_slots: ResMut<SaveSlots>,
+    mut pending_checkpoint: ResMut<PendingCheckpoint>,
     mut exit: EventWriter<AppExit>,
 ) {
-    for interaction in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            exit.send(AppExit::Success);
+    for MenuActionEvent(action) in action_events.read() {
+        match action {
+            MenuAction::Continue => {
+                if let Some(save) = save_slots.active_save() {
+                    pending_checkpoint.0 = Some(save.clone());
+                    next_state.set(GameState::InGame);
+                }
+            }
+            MenuAction::Play => {
+                pending_checkpoint.0 = None;
+                next_state.set(GameState::InGame);
+            }
+            MenuAction::SaveSlot => {
+                save_slots.select_next();
+
+                // Reconstruire le menu ("Continuer" dépend de l'emplacement actif)
+                // en gardant le bouton d'emplacement sélectionné
+                for entity in root_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                spawn_menu_root(
+                    &mut commands,
+                    &mut selected,
+                    &save_slots,
+                    Some(MenuAction::SaveSlot),
+                );
+            }
+            MenuAction::Options => settings_state.set(SettingsMenuState::Open),
+            MenuAction::Quit => {
+                exit.send(AppExit::Success);
+            }
         }
     }
 }
@@ -1,7 +1,12 @@
 use bevy::prelude::*;
 
-use crate::config::{AudioConfig, GameConfig, KeyBindings};
-use crate::input::GamepadState;
+use crate::audio::AudioChannel;
+use crate::config::{AudioConfig, DisplayConfig, GameConfig, WindowModeSetting};
+use crate::core::display::RESOLUTION_PRESETS;
+use crate::input::actions::{Action, ActionMap, InputBinding};
+use crate::input::{
+    is_button_just_pressed, is_dpad_down_just_pressed, is_dpad_up_just_pressed, GamepadState,
+};
 
 /// Marker for settings menu entities
 #[derive(Component)]
@@ -13,96 +18,151 @@ pub struct BackButton;
 
 /// Marker for volume value text
 #[derive(Component)]
-pub struct VolumeValueText;
+pub struct VolumeValueText(pub AudioChannel);
 
 /// Marker for decrease volume button
 #[derive(Component)]
-pub struct VolumeDecreaseButton;
+pub struct VolumeDecreaseButton(pub AudioChannel);
 
 /// Marker for increase volume button
 #[derive(Component)]
-pub struct VolumeIncreaseButton;
+pub struct VolumeIncreaseButton(pub AudioChannel);
 
-/// Marker for key binding buttons
-#[derive(Component)]
-pub struct KeyBindingButton {
-    pub action: KeyAction,
-}
-
-/// Marker for the key bindings section container (to hide when gamepad is connected)
-#[derive(Component)]
-pub struct KeyBindingsSection;
-
-/// Marker for the instructions text (to hide when gamepad is connected)
-#[derive(Component)]
-pub struct KeyBindingsInstructions;
-
-/// Key actions that can be remapped
+/// Display options shown in the settings
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum KeyAction {
-    MoveLeft,
-    MoveRight,
-    MoveUp,
-    MoveDown,
-    Jump,
-    Attack,
-    Interact,
+pub enum DisplaySetting {
+    WindowMode,
+    Resolution,
+    Vsync,
+    PixelScaling,
 }
 
-impl KeyAction {
+impl DisplaySetting {
+    pub const ALL: [DisplaySetting; 4] = [
+        DisplaySetting::WindowMode,
+        DisplaySetting::Resolution,
+        DisplaySetting::Vsync,
+        DisplaySetting::PixelScaling,
+    ];
+
     pub fn label(&self) -> &'static str {
         match self {
-            KeyAction::MoveLeft => "Gauche",
-            KeyAction::MoveRight => "Droite",
-            KeyAction::MoveUp => "Haut",
-            KeyAction::MoveDown => "Bas",
-            KeyAction::Jump => "Sauter",
-            KeyAction::Attack => "Attaquer",
-            KeyAction::Interact => "Interagir",
+            DisplaySetting::WindowMode => "Mode",
+            DisplaySetting::Resolution => "Résolution",
+            DisplaySetting::Vsync => "Synchro verticale",
+            DisplaySetting::PixelScaling => "Pixels entiers",
         }
     }
 
-    pub fn get_key(&self, bindings: &KeyBindings) -> KeyCode {
+    /// Value shown on the button of the setting
+    pub fn value(&self, display_config: &DisplayConfig) -> String {
+        let on_off = |enabled: bool| if enabled { "Oui" } else { "Non" }.to_string();
         match self {
-            KeyAction::MoveLeft => bindings.move_left,
-            KeyAction::MoveRight => bindings.move_right,
-            KeyAction::MoveUp => bindings.move_up,
-            KeyAction::MoveDown => bindings.move_down,
-            KeyAction::Jump => bindings.jump,
-            KeyAction::Attack => bindings.attack,
-            KeyAction::Interact => bindings.interact,
+            DisplaySetting::WindowMode => display_config.window_mode.label().to_string(),
+            DisplaySetting::Resolution => format!(
+                "{} x {}",
+                display_config.resolution.x, display_config.resolution.y
+            ),
+            DisplaySetting::Vsync => on_off(display_config.vsync),
+            DisplaySetting::PixelScaling => on_off(display_config.pixel_scaling),
         }
     }
 
-    pub fn set_key(&self, bindings: &mut KeyBindings, key: KeyCode) {
+    /// Switch the setting to its next value (wrapping around)
+    pub fn cycle(&self, display_config: &mut DisplayConfig) {
+        fn next<T: PartialEq + Copy>(values: &[T], current: T) -> T {
+            let index = values.iter().position(|value| *value == current);
+            values[index.map_or(0, |index| (index + 1) % values.len())]
+        }
+
         match self {
-            KeyAction::MoveLeft => bindings.move_left = key,
-            KeyAction::MoveRight => bindings.move_right = key,
-            KeyAction::MoveUp => bindings.move_up = key,
-            KeyAction::MoveDown => bindings.move_down = key,
-            KeyAction::Jump => bindings.jump = key,
-            KeyAction::Attack => bindings.attack = key,
-            KeyAction::Interact => bindings.interact = key,
+            DisplaySetting::WindowMode => {
+                display_config.window_mode =
+                    next(&WindowModeSetting::ALL, display_config.window_mode);
+            }
+            DisplaySetting::Resolution => {
+                display_config.resolution = next(&RESOLUTION_PRESETS, display_config.resolution);
+            }
+            DisplaySetting::Vsync => display_config.vsync = !display_config.vsync,
+            DisplaySetting::PixelScaling => {
+                display_config.pixel_scaling = !display_config.pixel_scaling;
+            }
         }
     }
 }
 
-/// State for which key is being edited (if any)
-#[derive(Resource, Default)]
-pub struct EditingKeyBinding {
-    pub action: Option<KeyAction>,
+/// Marker for the buttons cycling through the values of a display setting
+#[derive(Component)]
+pub struct DisplaySettingButton(pub DisplaySetting);
+
+/// Marker for display setting value text
+#[derive(Component)]
+pub struct DisplayValueText(pub DisplaySetting);
+
+/// Column of the bindings table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Key,
+    GamepadButton,
+}
+
+/// Cell of the bindings table: one binding of one action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingSlot {
+    pub action: Action,
+    pub kind: BindingKind,
+}
+
+impl BindingSlot {
+    /// Binding currently shown in this cell
+    pub fn binding(&self, action_map: &ActionMap) -> Option<InputBinding> {
+        match self.kind {
+            BindingKind::Key => action_map.key(self.action).map(InputBinding::Key),
+            BindingKind::GamepadButton => action_map
+                .gamepad_button(self.action)
+                .map(InputBinding::GamepadButton),
+        }
+    }
 }
 
-/// Marker for key value text components
+/// Marker for binding buttons (keyboard key or gamepad button of an action)
+#[derive(Component)]
+pub struct BindingButton(pub BindingSlot);
+
+/// Marker for binding value text components
+#[derive(Component)]
+pub struct BindingValueText(pub BindingSlot);
+
+/// Marker for the bindings section container
+#[derive(Component)]
+pub struct KeyBindingsSection;
+
+/// Marker for the instructions text below the bindings
+#[derive(Component)]
+pub struct KeyBindingsInstructions;
+
+/// Marker for the text listing the bindings shared by several actions
 #[derive(Component)]
-pub struct KeyValueText {
-    pub action: KeyAction,
+pub struct BindingConflictsText;
+
+/// State for which binding is being edited (if any)
+#[derive(Resource, Default)]
+pub struct EditingBinding {
+    pub slot: Option<BindingSlot>,
+}
+
+/// Row of the bindings table selected with the gamepad (index in `Action::ALL`)
+#[derive(Resource, Default)]
+pub struct SelectedBindingRow {
+    pub index: usize,
 }
 
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const SELECTED_BUTTON: Color = Color::srgb(0.3, 0.5, 0.8);
 const EDITING_BUTTON: Color = Color::srgb(0.8, 0.5, 0.3);
+const CONFLICT_BUTTON: Color = Color::srgb(0.6, 0.15, 0.15);
+const CONFLICT_TEXT: Color = Color::srgb(0.9, 0.4, 0.4);
 
 /// Convert KeyCode to display string
 pub fn keycode_to_string(key: KeyCode) -> String {
@@ -161,15 +221,259 @@ pub fn keycode_to_string(key: KeyCode) -> String {
     }
 }
 
+/// Convert GamepadButton to display string (Xbox layout)
+pub fn gamepad_button_to_string(button: GamepadButton) -> String {
+    match button {
+        GamepadButton::South => "A".to_string(),
+        GamepadButton::East => "B".to_string(),
+        GamepadButton::West => "X".to_string(),
+        GamepadButton::North => "Y".to_string(),
+        GamepadButton::LeftTrigger => "LB".to_string(),
+        GamepadButton::RightTrigger => "RB".to_string(),
+        GamepadButton::LeftTrigger2 => "LT".to_string(),
+        GamepadButton::RightTrigger2 => "RT".to_string(),
+        GamepadButton::LeftThumb => "L3".to_string(),
+        GamepadButton::RightThumb => "R3".to_string(),
+        GamepadButton::Select => "Select".to_string(),
+        GamepadButton::Start => "Start".to_string(),
+        GamepadButton::Mode => "Home".to_string(),
+        GamepadButton::DPadUp => "Croix ↑".to_string(),
+        GamepadButton::DPadDown => "Croix ↓".to_string(),
+        GamepadButton::DPadLeft => "Croix ←".to_string(),
+        GamepadButton::DPadRight => "Croix →".to_string(),
+        GamepadButton::Other(index) => format!("Bouton {}", index),
+        _ => format!("{:?}", button),
+    }
+}
+
+/// Convert any binding to display string
+pub fn binding_to_string(binding: InputBinding) -> String {
+    match binding {
+        InputBinding::Key(key) => keycode_to_string(key),
+        InputBinding::GamepadButton(button) => gamepad_button_to_string(button),
+        InputBinding::Mouse(button) => format!("Souris {:?}", button),
+        InputBinding::GamepadAxis { axis, positive } => {
+            format!("{:?} {}", axis, if positive { "+" } else { "-" })
+        }
+    }
+}
+
+/// Text listing the bindings shared by several actions (empty without conflict)
+fn conflicts_message(action_map: &ActionMap) -> String {
+    action_map
+        .conflicts()
+        .into_iter()
+        .map(|(binding, actions)| {
+            let labels: Vec<&str> = actions.iter().map(Action::label).collect();
+            format!(
+                "Conflit : {} est utilisé par {}",
+                binding_to_string(binding),
+                labels.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Spawn the button of one cell of the bindings table
+fn spawn_binding_button(parent: &mut ChildBuilder, slot: BindingSlot, action_map: &ActionMap) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(100.0),
+                height: Val::Px(35.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+            BorderRadius::all(Val::Px(5.0)),
+            BindingButton(slot),
+        ))
+        .with_children(|parent| {
+            let label = slot
+                .binding(action_map)
+                .map(binding_to_string)
+                .unwrap_or_default();
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                BindingValueText(slot),
+            ));
+        });
+}
+
+/// Spawn the small square button of a volume row
+fn spawn_volume_button(parent: &mut ChildBuilder, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(36.0),
+                height: Val::Px(36.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+            BorderRadius::all(Val::Px(5.0)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+/// Spawn the row of one volume setting: label, decrease button, value and increase button
+fn spawn_volume_row(parent: &mut ChildBuilder, channel: AudioChannel, audio_config: &AudioConfig) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(channel.label()),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    width: Val::Px(150.0),
+                    ..default()
+                },
+            ));
+
+            spawn_volume_button(parent, "-", VolumeDecreaseButton(channel));
+
+            parent.spawn((
+                Text::new(volume_to_string(audio_config.volume(channel))),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    width: Val::Px(60.0),
+                    ..default()
+                },
+                VolumeValueText(channel),
+            ));
+
+            spawn_volume_button(parent, "+", VolumeIncreaseButton(channel));
+        });
+}
+
+/// Spawn the row of one display setting: label and a button showing its value
+fn spawn_display_row(
+    parent: &mut ChildBuilder,
+    setting: DisplaySetting,
+    display_config: &DisplayConfig,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(setting.label()),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    width: Val::Px(150.0),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(162.0),
+                        height: Val::Px(36.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                    BorderRadius::all(Val::Px(5.0)),
+                    DisplaySettingButton(setting),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(setting.value(display_config)),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        DisplayValueText(setting),
+                    ));
+                });
+        });
+}
+
+/// Volume shown in the settings, in percent
+fn volume_to_string(volume: f32) -> String {
+    format!("{}%", (volume * 100.0).round() as i32)
+}
+
+/// Title of a column of the settings
+fn spawn_section_title(parent: &mut ChildBuilder, title: &str) {
+    parent.spawn((
+        Text::new(title),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        Node {
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+    ));
+}
+
 pub fn setup_settings_menu(
     mut commands: Commands,
-    key_bindings: Res<KeyBindings>,
+    action_map: Res<ActionMap>,
     audio_config: Res<AudioConfig>,
-    gamepad_state: Res<GamepadState>,
+    display_config: Res<DisplayConfig>,
+    mut selected_row: ResMut<SelectedBindingRow>,
 ) {
-    let gamepad_connected = gamepad_state.is_connected();
-    // Camera for the settings UI
-    commands.spawn((Camera2d, SettingsEntity));
+    selected_row.index = 0;
+
+    // Camera for the settings UI, drawn over the game cameras when opened from the pause menu
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: 1,
+            ..default()
+        },
+        SettingsEntity,
+    ));
 
     // Main container
     commands
@@ -197,229 +501,144 @@ pub fn setup_settings_menu(
                 },
                 TextColor(Color::srgb(0.9, 0.9, 0.9)),
                 Node {
-                    margin: UiRect::bottom(Val::Px(30.0)),
+                    margin: UiRect::bottom(Val::Px(10.0)),
                     ..default()
                 },
             ));
 
-            // Volume section
+            // Settings columns: audio and display on the left, controls on the right
             parent
                 .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    row_gap: Val::Px(10.0),
-                    margin: UiRect::bottom(Val::Px(20.0)),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::FlexStart,
+                    column_gap: Val::Px(60.0),
                     ..default()
                 })
                 .with_children(|parent| {
-                    // Volume label
-                    parent.spawn((
-                        Text::new("Volume Principal"),
-                        TextFont {
-                            font_size: 24.0,
-                            ..default()
-                        },
-                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
-                    ));
-
-                    // Volume controls row
+                    // Audio and display sections
                     parent
                         .spawn(Node {
-                            flex_direction: FlexDirection::Row,
+                            flex_direction: FlexDirection::Column,
                             align_items: AlignItems::Center,
-                            column_gap: Val::Px(15.0),
+                            row_gap: Val::Px(10.0),
                             ..default()
                         })
                         .with_children(|parent| {
-                            // Decrease button
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(40.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..default()
-                                    },
-                                    BackgroundColor(NORMAL_BUTTON),
-                                    BorderRadius::all(Val::Px(5.0)),
-                                    VolumeDecreaseButton,
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        Text::new("-"),
-                                        TextFont {
-                                            font_size: 28.0,
-                                            ..default()
-                                        },
-                                        TextColor(Color::WHITE),
-                                    ));
-                                });
-
-                            // Volume value display
-                            parent.spawn((
-                                Text::new(format!("{}%", (audio_config.master_volume * 100.0) as i32)),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                                Node {
-                                    width: Val::Px(60.0),
-                                    justify_content: JustifyContent::Center,
-                                    ..default()
-                                },
-                                VolumeValueText,
-                            ));
-
-                            // Increase button
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(40.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..default()
-                                    },
-                                    BackgroundColor(NORMAL_BUTTON),
-                                    BorderRadius::all(Val::Px(5.0)),
-                                    VolumeIncreaseButton,
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        Text::new("+"),
-                                        TextFont {
-                                            font_size: 28.0,
-                                            ..default()
-                                        },
-                                        TextColor(Color::WHITE),
-                                    ));
-                                });
+                            spawn_section_title(parent, "Audio");
+                            for channel in AudioChannel::ALL {
+                                spawn_volume_row(parent, channel, &audio_config);
+                            }
+
+                            spawn_section_title(parent, "Affichage");
+                            for setting in DisplaySetting::ALL {
+                                spawn_display_row(parent, setting, &display_config);
+                            }
                         });
-                });
 
-            // Key bindings section - only show when no gamepad is connected
-            if !gamepad_connected {
-                parent
-                    .spawn((
-                        Node {
-                            flex_direction: FlexDirection::Column,
-                            align_items: AlignItems::Center,
-                            row_gap: Val::Px(8.0),
-                            margin: UiRect::bottom(Val::Px(20.0)),
-                            ..default()
-                        },
-                        KeyBindingsSection,
-                    ))
-                    .with_children(|parent| {
-                        // Key bindings label
-                        parent.spawn((
-                            Text::new("Contrôles"),
-                            TextFont {
-                                font_size: 24.0,
-                                ..default()
-                            },
-                            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                    // Bindings section: keyboard key and gamepad button of each action
+                    parent
+                        .spawn((
                             Node {
-                                margin: UiRect::bottom(Val::Px(10.0)),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(6.0),
                                 ..default()
                             },
-                        ));
-
-                        // Key binding rows
-                        let actions = [
-                            KeyAction::MoveLeft,
-                            KeyAction::MoveRight,
-                            KeyAction::MoveUp,
-                            KeyAction::MoveDown,
-                            KeyAction::Jump,
-                            KeyAction::Attack,
-                            KeyAction::Interact,
-                        ];
-
-                        for action in actions {
+                            KeyBindingsSection,
+                        ))
+                        .with_children(|parent| {
+                            spawn_section_title(parent, "Contrôles");
+
+                            // Column headers
                             parent
                                 .spawn(Node {
                                     flex_direction: FlexDirection::Row,
                                     align_items: AlignItems::Center,
-                                    justify_content: JustifyContent::SpaceBetween,
-                                    width: Val::Px(300.0),
+                                    column_gap: Val::Px(10.0),
+                                    width: Val::Px(360.0),
                                     ..default()
                                 })
                                 .with_children(|parent| {
-                                    // Action label
-                                    parent.spawn((
-                                        Text::new(action.label()),
-                                        TextFont {
-                                            font_size: 20.0,
-                                            ..default()
-                                        },
-                                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
-                                    ));
-
-                                    // Key button
-                                    parent
-                                        .spawn((
-                                            Button,
+                                    for (label, width) in
+                                        [("", 140.0), ("Clavier", 100.0), ("Manette", 100.0)]
+                                    {
+                                        parent.spawn((
+                                            Text::new(label),
+                                            TextFont {
+                                                font_size: 16.0,
+                                                ..default()
+                                            },
+                                            TextColor(Color::srgb(0.5, 0.5, 0.5)),
+                                            TextLayout::new_with_justify(JustifyText::Center),
                                             Node {
-                                                width: Val::Px(100.0),
-                                                height: Val::Px(35.0),
-                                                justify_content: JustifyContent::Center,
-                                                align_items: AlignItems::Center,
+                                                width: Val::Px(width),
                                                 ..default()
                                             },
-                                            BackgroundColor(NORMAL_BUTTON),
-                                            BorderRadius::all(Val::Px(5.0)),
-                                            KeyBindingButton { action },
-                                        ))
-                                        .with_children(|parent| {
-                                            let key = action.get_key(&key_bindings);
-                                            parent.spawn((
-                                                Text::new(keycode_to_string(key)),
-                                                TextFont {
-                                                    font_size: 18.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::WHITE),
-                                                KeyValueText { action },
-                                            ));
-                                        });
+                                        ));
+                                    }
                                 });
-                        }
-                    });
-
-                // Instructions for keyboard controls
-                parent.spawn((
-                    Text::new("Cliquez sur une touche pour la modifier"),
-                    TextFont {
-                        font_size: 14.0,
-                        ..default()
-                    },
-                    TextColor(Color::srgb(0.5, 0.5, 0.5)),
-                    Node {
-                        margin: UiRect::bottom(Val::Px(20.0)),
-                        ..default()
-                    },
-                    KeyBindingsInstructions,
-                ));
-            } else {
-                // Show message when gamepad is connected
-                parent.spawn((
-                    Text::new("Manette détectée - Contrôles clavier désactivés"),
-                    TextFont {
-                        font_size: 18.0,
-                        ..default()
-                    },
-                    TextColor(Color::srgb(0.6, 0.8, 0.6)),
-                    Node {
-                        margin: UiRect::bottom(Val::Px(20.0)),
-                        ..default()
-                    },
-                ));
-            }
+
+                            // Binding rows
+                            for action in Action::ALL {
+                                parent
+                                    .spawn(Node {
+                                        flex_direction: FlexDirection::Row,
+                                        align_items: AlignItems::Center,
+                                        column_gap: Val::Px(10.0),
+                                        width: Val::Px(360.0),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        // Action label
+                                        parent.spawn((
+                                            Text::new(action.label()),
+                                            TextFont {
+                                                font_size: 20.0,
+                                                ..default()
+                                            },
+                                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                                            Node {
+                                                width: Val::Px(140.0),
+                                                ..default()
+                                            },
+                                        ));
+
+                                        for kind in [BindingKind::Key, BindingKind::GamepadButton] {
+                                            spawn_binding_button(
+                                                parent,
+                                                BindingSlot { action, kind },
+                                                &action_map,
+                                            );
+                                        }
+                                    });
+                            }
+
+                            // Bindings shared by several actions
+                            parent.spawn((
+                                Text::new(conflicts_message(&action_map)),
+                                TextFont {
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                                TextColor(CONFLICT_TEXT),
+                                TextLayout::new_with_justify(JustifyText::Center),
+                                BindingConflictsText,
+                            ));
+                        });
+                });
+
+            // Instructions for the bindings
+            parent.spawn((
+                Text::new(
+                    "Cliquez sur une touche ou appuyez sur A pour la modifier  |  Échap / Start : Annuler",
+                ),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.5, 0.5, 0.5)),
+                KeyBindingsInstructions,
+            ));
 
             // Back button
             parent
@@ -452,42 +671,44 @@ pub fn setup_settings_menu(
 pub fn cleanup_settings_menu(
     mut commands: Commands,
     query: Query<Entity, With<SettingsEntity>>,
-    mut editing: ResMut<EditingKeyBinding>,
+    mut editing: ResMut<EditingBinding>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    editing.action = None;
+    editing.slot = None;
 }
 
 pub fn handle_volume_buttons(
-    decrease_query: Query<&Interaction, (Changed<Interaction>, With<VolumeDecreaseButton>)>,
-    increase_query: Query<&Interaction, (Changed<Interaction>, With<VolumeIncreaseButton>)>,
+    decrease_query: Query<(&Interaction, &VolumeDecreaseButton), Changed<Interaction>>,
+    increase_query: Query<(&Interaction, &VolumeIncreaseButton), Changed<Interaction>>,
     mut audio_config: ResMut<AudioConfig>,
     mut config: ResMut<GameConfig>,
-    mut volume_text: Query<&mut Text, With<VolumeValueText>>,
+    mut volume_text: Query<(&VolumeValueText, &mut Text)>,
     mut global_volume: ResMut<GlobalVolume>,
 ) {
     let mut changed = false;
 
-    for interaction in decrease_query.iter() {
+    for (interaction, VolumeDecreaseButton(channel)) in decrease_query.iter() {
         if *interaction == Interaction::Pressed {
-            audio_config.master_volume = (audio_config.master_volume - 0.05).max(0.0);
+            let volume = audio_config.volume_mut(*channel);
+            *volume = (*volume - 0.05).max(0.0);
             changed = true;
         }
     }
 
-    for interaction in increase_query.iter() {
+    for (interaction, VolumeIncreaseButton(channel)) in increase_query.iter() {
         if *interaction == Interaction::Pressed {
-            audio_config.master_volume = (audio_config.master_volume + 0.05).min(1.0);
+            let volume = audio_config.volume_mut(*channel);
+            *volume = (*volume + 0.05).min(1.0);
             changed = true;
         }
     }
 
     if changed {
         // Update the display
-        for mut text in volume_text.iter_mut() {
-            **text = format!("{}%", (audio_config.master_volume * 100.0) as i32);
+        for (VolumeValueText(channel), mut text) in volume_text.iter_mut() {
+            **text = volume_to_string(audio_config.volume(*channel));
         }
 
         // Update config and save
@@ -501,77 +722,222 @@ pub fn handle_volume_buttons(
     }
 }
 
-pub fn handle_key_binding_buttons(
-    interaction_query: Query<
-        (&Interaction, &KeyBindingButton, &mut BackgroundColor),
-        Changed<Interaction>,
-    >,
-    mut editing: ResMut<EditingKeyBinding>,
+pub fn handle_display_buttons(
+    interaction_query: Query<(&Interaction, &DisplaySettingButton), Changed<Interaction>>,
+    mut display_config: ResMut<DisplayConfig>,
+    mut config: ResMut<GameConfig>,
+    mut value_text: Query<(&DisplayValueText, &mut Text)>,
+) {
+    let mut changed = false;
+
+    for (interaction, DisplaySettingButton(setting)) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            setting.cycle(&mut display_config);
+            changed = true;
+        }
+    }
+
+    if changed {
+        // Update the display
+        for (DisplayValueText(setting), mut text) in value_text.iter_mut() {
+            **text = setting.value(&display_config);
+        }
+
+        // Update config and save (the window follows the resource)
+        config.display = display_config.clone();
+        if let Err(e) = config.save() {
+            warn!("Failed to save config: {}", e);
+        }
+    }
+}
+
+pub fn handle_binding_buttons(
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    mut editing: ResMut<EditingBinding>,
+    mut selected_row: ResMut<SelectedBindingRow>,
 ) {
-    for (interaction, button, _color) in interaction_query.iter() {
+    for (interaction, BindingButton(slot)) in interaction_query.iter() {
         if *interaction == Interaction::Pressed {
-            if editing.action == Some(button.action) {
+            if editing.slot == Some(*slot) {
                 // Cancel editing
-                editing.action = None;
+                editing.slot = None;
             } else {
-                // Start editing this key
-                editing.action = Some(button.action);
+                // Start editing this binding
+                editing.slot = Some(*slot);
+                if let Some(index) = Action::ALL.iter().position(|a| *a == slot.action) {
+                    selected_row.index = index;
+                }
             }
         }
     }
 }
 
-pub fn update_key_binding_button_colors(
-    editing: Res<EditingKeyBinding>,
-    mut button_query: Query<(&KeyBindingButton, &mut BackgroundColor, &Interaction)>,
+pub fn update_binding_button_colors(
+    editing: Res<EditingBinding>,
+    selected_row: Res<SelectedBindingRow>,
+    gamepad_state: Res<GamepadState>,
+    action_map: Res<ActionMap>,
+    mut button_query: Query<(&BindingButton, &mut BackgroundColor, &Interaction)>,
 ) {
-    for (button, mut color, interaction) in button_query.iter_mut() {
-        if editing.action == Some(button.action) {
-            *color = EDITING_BUTTON.into();
+    for (BindingButton(slot), mut color, interaction) in button_query.iter_mut() {
+        let selected = gamepad_state.is_connected()
+            && slot.kind == BindingKind::GamepadButton
+            && Action::ALL[selected_row.index] == slot.action;
+        let conflict = slot
+            .binding(&action_map)
+            .is_some_and(|binding| action_map.actions_bound_to(binding).len() > 1);
+
+        *color = if editing.slot == Some(*slot) {
+            EDITING_BUTTON
         } else if *interaction == Interaction::Hovered {
-            *color = HOVERED_BUTTON.into();
+            HOVERED_BUTTON
+        } else if selected {
+            SELECTED_BUTTON
+        } else if conflict {
+            CONFLICT_BUTTON
         } else {
-            *color = NORMAL_BUTTON.into();
+            NORMAL_BUTTON
         }
+        .into();
+    }
+}
+
+/// Show the new binding of a slot and save the configuration
+fn apply_binding_change(
+    slot: BindingSlot,
+    action_map: &ActionMap,
+    config: &mut GameConfig,
+    text_query: &mut Query<(&BindingValueText, &mut Text)>,
+) {
+    for (BindingValueText(text_slot), mut text) in text_query.iter_mut() {
+        if *text_slot == slot {
+            **text = slot
+                .binding(action_map)
+                .map(binding_to_string)
+                .unwrap_or_default();
+        }
+    }
+
+    config.actions = action_map.clone();
+    if let Err(e) = config.save() {
+        warn!("Failed to save config: {}", e);
     }
 }
 
 pub fn capture_key_input(
     input: Res<ButtonInput<KeyCode>>,
-    mut editing: ResMut<EditingKeyBinding>,
-    mut key_bindings: ResMut<KeyBindings>,
+    mut editing: ResMut<EditingBinding>,
+    mut action_map: ResMut<ActionMap>,
     mut config: ResMut<GameConfig>,
-    mut key_text_query: Query<(&KeyValueText, &mut Text)>,
+    mut text_query: Query<(&BindingValueText, &mut Text)>,
 ) {
-    if let Some(action) = editing.action {
-        // Check for any key press
-        for key in input.get_just_pressed() {
-            // Skip escape (used to cancel)
-            if *key == KeyCode::Escape {
-                editing.action = None;
-                return;
-            }
+    let Some(slot) = editing.slot else {
+        return;
+    };
 
-            // Set the new key
-            action.set_key(&mut key_bindings, *key);
+    // Escape cancels the edition of any binding
+    if input.just_pressed(KeyCode::Escape) {
+        editing.slot = None;
+        return;
+    }
 
-            // Update the text display
-            for (text_action, mut text) in key_text_query.iter_mut() {
-                if text_action.action == action {
-                    **text = keycode_to_string(*key);
-                }
-            }
+    if slot.kind != BindingKind::Key {
+        return;
+    }
 
-            // Update config and save
-            config.key_bindings = key_bindings.clone();
-            if let Err(e) = config.save() {
-                warn!("Failed to save config: {}", e);
-            }
+    // Check for any key press
+    if let Some(key) = input.get_just_pressed().next() {
+        action_map.set_key(slot.action, *key);
+        apply_binding_change(slot, &action_map, &mut config, &mut text_query);
 
-            // Stop editing
-            editing.action = None;
-            return;
-        }
+        // Stop editing
+        editing.slot = None;
+    }
+}
+
+pub fn capture_gamepad_input(
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut editing: ResMut<EditingBinding>,
+    mut action_map: ResMut<ActionMap>,
+    mut config: ResMut<GameConfig>,
+    mut text_query: Query<(&BindingValueText, &mut Text)>,
+) {
+    let Some(slot) = editing.slot else {
+        return;
+    };
+    let Some(gamepad) = gamepad_state
+        .active_gamepad
+        .and_then(|entity| gamepads.get(entity).ok())
+    else {
+        return;
+    };
+
+    // Start cancels the edition of any binding
+    if gamepad.just_pressed(GamepadButton::Start) {
+        editing.slot = None;
+        return;
+    }
+
+    if slot.kind != BindingKind::GamepadButton {
+        return;
+    }
+
+    // Check for any button press
+    if let Some(button) = gamepad.get_just_pressed().next() {
+        action_map.set_gamepad_button(slot.action, *button);
+        apply_binding_change(slot, &action_map, &mut config, &mut text_query);
+
+        // Stop editing
+        editing.slot = None;
+    }
+}
+
+/// Select a row of the bindings table with the D-pad and edit its gamepad button with A
+pub fn navigate_binding_rows(
+    gamepad_state: Res<GamepadState>,
+    gamepads: Query<&Gamepad>,
+    mut editing: ResMut<EditingBinding>,
+    mut selected_row: ResMut<SelectedBindingRow>,
+) {
+    // The buttons pressed while editing (or on the frame the edition ends) are the new binding
+    if editing.slot.is_some() || editing.is_changed() {
+        return;
+    }
+    let Some(gamepad_entity) = gamepad_state.active_gamepad else {
+        return;
+    };
+
+    let mut direction: i32 = 0;
+    if is_dpad_up_just_pressed(&gamepads, gamepad_entity) {
+        direction = -1;
+    }
+    if is_dpad_down_just_pressed(&gamepads, gamepad_entity) {
+        direction = 1;
+    }
+    if direction != 0 {
+        let total = Action::ALL.len() as i32;
+        selected_row.index = (selected_row.index as i32 + direction).rem_euclid(total) as usize;
+    }
+
+    if is_button_just_pressed(&gamepads, gamepad_entity, GamepadButton::South) {
+        editing.slot = Some(BindingSlot {
+            action: Action::ALL[selected_row.index],
+            kind: BindingKind::GamepadButton,
+        });
+    }
+}
+
+/// Keep the list of conflicting bindings up to date
+pub fn update_binding_conflicts(
+    action_map: Res<ActionMap>,
+    mut text_query: Query<&mut Text, With<BindingConflictsText>>,
+) {
+    if !action_map.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        **text = conflicts_message(&action_map);
     }
 }
 
@@ -579,10 +945,14 @@ pub fn handle_back_button(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
     gamepad_state: Res<GamepadState>,
     gamepads: Query<&Gamepad>,
+    editing: Res<EditingBinding>,
     mut next_state: ResMut<NextState<super::SettingsMenuState>>,
 ) {
-    // Check for back action from gamepad (East button / B) or mouse click
-    let back_pressed = if let Some(gamepad_entity) = gamepad_state.active_gamepad {
+    // Check for back action from gamepad (East button / B) or mouse click; B is a regular
+    // binding while a gamepad button is being edited
+    let back_pressed = if editing.slot.is_some() || editing.is_changed() {
+        false
+    } else if let Some(gamepad_entity) = gamepad_state.active_gamepad {
         if let Ok(gamepad) = gamepads.get(gamepad_entity) {
             gamepad.just_pressed(GamepadButton::East)
         } else {
@@ -604,12 +974,16 @@ pub fn handle_back_button(
     }
 }
 
-pub fn handle_volume_button_hover(
+pub fn handle_option_button_hover(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor),
         (
             Changed<Interaction>,
-            Or<(With<VolumeDecreaseButton>, With<VolumeIncreaseButton>)>,
+            Or<(
+                With<VolumeDecreaseButton>,
+                With<VolumeIncreaseButton>,
+                With<DisplaySettingButton>,
+            )>,
         ),
     >,
 ) {
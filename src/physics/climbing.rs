@@ -1,18 +1,38 @@
-use bevy::{prelude::*, utils::HashSet};
+//! Ladders of the LDtk levels.
+//!
+//! The player grabs a ladder by moving up or down in front of it, and is centered on its
+//! column while climbing. The top of each ladder holds bodies like a one-way platform:
+//! the player stands on it, and climbs down by holding down.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use super::colliders::SensorBundle;
+use super::ground_detection::{GroundDetection, GroundSensor};
+use super::one_way_platforms::OneWayPlatformCollider;
+
+/// Thickness of the plate standing on top of each ladder
+const LADDER_TOP_THICKNESS: f32 = 2.;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Climber {
     pub climbing: bool,
     pub intersecting_climbables: HashSet<Entity>,
+    /// Standing on the top of a ladder, that can be climbed down
+    pub on_ladder_top: bool,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Climbable;
 
+/// Marker of the one-way plate spawned on the top cell of a ladder
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct LadderTop;
+
 #[derive(Clone, Default, Bundle, LdtkIntCell)]
 pub struct LadderBundle {
     #[from_int_grid_cell]
@@ -56,6 +76,89 @@ pub fn detect_climb_range(
     }
 }
 
+/// Système qui pose une plateforme à sens unique sur la case la plus haute de chaque échelle
+pub fn spawn_ladder_tops(
+    mut commands: Commands,
+    ladder_query: Query<(Entity, &GridCoords, &Parent), Added<Climbable>>,
+    layer_query: Query<&LayerMetadata>,
+) {
+    let mut layer_to_ladder_locations: HashMap<Entity, HashSet<GridCoords>> = HashMap::new();
+    for (_, &grid_coords, parent) in &ladder_query {
+        layer_to_ladder_locations
+            .entry(parent.get())
+            .or_default()
+            .insert(grid_coords);
+    }
+
+    for (entity, &grid_coords, parent) in &ladder_query {
+        let Ok(layer) = layer_query.get(parent.get()) else {
+            continue;
+        };
+        let above = grid_coords + GridCoords::new(0, 1);
+        if layer_to_ladder_locations[&parent.get()].contains(&above) {
+            continue;
+        }
+
+        let grid_size = layer.grid_size as f32;
+        commands.entity(entity).with_children(|ladder| {
+            ladder.spawn((
+                Collider::cuboid(grid_size / 2., LADDER_TOP_THICKNESS / 2.),
+                RigidBody::Fixed,
+                Friction::new(1.0),
+                ActiveHooks::MODIFY_SOLVER_CONTACTS,
+                OneWayPlatformCollider,
+                LadderTop,
+                Transform::from_xyz(0., (grid_size - LADDER_TOP_THICKNESS) / 2., 0.),
+            ));
+        });
+    }
+}
+
+/// Système qui détecte les grimpeurs debout sur le haut d'une échelle
+pub fn detect_ladder_tops(
+    mut climbers: Query<(&mut Climber, &GroundDetection)>,
+    ground_sensors: Query<&GroundSensor>,
+    ladder_tops: Query<(), With<LadderTop>>,
+) {
+    for sensor in &ground_sensors {
+        if let Ok((mut climber, ground_detection)) =
+            climbers.get_mut(sensor.ground_detection_entity)
+        {
+            let on_ladder_top = ground_detection.on_platform
+                && sensor
+                    .intersecting_ground_entities
+                    .iter()
+                    .any(|&ground| ladder_tops.contains(ground));
+            if climber.on_ladder_top != on_ladder_top {
+                climber.on_ladder_top = on_ladder_top;
+            }
+        }
+    }
+}
+
+/// Système qui centre les grimpeurs sur la colonne de l'échelle
+pub fn snap_to_ladder(
+    mut climbers: Query<(&Climber, &mut Transform, &GlobalTransform), Changed<Climber>>,
+    climbables: Query<&GlobalTransform, With<Climbable>>,
+) {
+    for (climber, mut transform, global_transform) in &mut climbers {
+        if !climber.climbing {
+            continue;
+        }
+
+        let x = global_transform.translation().x;
+        let ladder_x = climber
+            .intersecting_climbables
+            .iter()
+            .filter_map(|&climbable| climbables.get(climbable).ok())
+            .map(|ladder| ladder.translation().x)
+            .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()));
+        if let Some(ladder_x) = ladder_x {
+            transform.translation.x += ladder_x - x;
+        }
+    }
+}
+
 pub fn ignore_gravity_if_climbing(
     mut query: Query<(&Climber, &mut GravityScale), Changed<Climber>>,
 ) {
@@ -73,7 +176,135 @@ pub struct ClimbingPlugin;
 impl Plugin for ClimbingPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, detect_climb_range)
+            .add_systems(
+                Update,
+                (spawn_ladder_tops, detect_ladder_tops, snap_to_ladder),
+            )
             .add_systems(Update, ignore_gravity_if_climbing)
             .register_ldtk_int_cell::<LadderBundle>(2);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::Climber;
+    use crate::physics::ground_detection::GroundDetection;
+    use crate::testing::TestGame;
+
+    /// Horizontal center of the ladder of the fixture
+    const LADDER_X: f32 = 328.0;
+    /// Height of the top of the ladder above the floor
+    const LADDER_HEIGHT: f32 = 144.0;
+
+    /// Puts the player at the foot of the ladder, returns it and its height on the floor
+    fn stand_at_the_ladder(game: &mut TestGame, x: f32) -> (Entity, f32) {
+        game.settle();
+        let player = game.player();
+        game.get_mut::<Transform>(player).translation.x = x;
+        game.settle();
+        (player, game.position(player).y)
+    }
+
+    /// Climbs to the top of the ladder and stands there
+    fn climb_to_the_top(game: &mut TestGame) -> (Entity, f32) {
+        let (player, ground_y) = stand_at_the_ladder(game, LADDER_X);
+        game.press_key(KeyCode::KeyW);
+        game.step(90);
+        game.release_key(KeyCode::KeyW);
+        game.settle();
+        (player, ground_y)
+    }
+
+    #[test]
+    fn player_climbs_ladders() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        game.get_mut::<Transform>(player).translation.x = LADDER_X;
+        game.step(5);
+        assert!(!game
+            .get::<Climber>(player)
+            .unwrap()
+            .intersecting_climbables
+            .is_empty());
+        let start_y = game.position(player).y;
+
+        game.press_key(KeyCode::KeyW);
+        game.step(20);
+        assert!(game.get::<Climber>(player).unwrap().climbing);
+        let climbed = game.position(player).y - start_y;
+        assert!(climbed > 30.0, "player should climb, rose {climbed}");
+
+        // Hanging on the ladder without gravity
+        game.release_key(KeyCode::KeyW);
+        game.step(20);
+        let y = game.position(player).y;
+        assert!((y - (start_y + climbed)).abs() < 8.0);
+    }
+
+    #[test]
+    fn grabbing_a_ladder_centers_the_player_on_it() {
+        let mut game = TestGame::new();
+        let (player, _) = stand_at_the_ladder(&mut game, LADDER_X - 5.0);
+
+        game.press_key(KeyCode::KeyW);
+        game.step(10);
+
+        assert!(game.get::<Climber>(player).unwrap().climbing);
+        let x = game.position(player).x;
+        assert!((x - LADDER_X).abs() < 0.5, "player is at {x}");
+    }
+
+    #[test]
+    fn player_leaves_a_ladder_sideways_only_by_jumping() {
+        let mut game = TestGame::new();
+        let (player, _) = stand_at_the_ladder(&mut game, LADDER_X);
+        game.press_key(KeyCode::KeyW);
+        game.step(20);
+        game.release_key(KeyCode::KeyW);
+
+        game.press_key(KeyCode::KeyD);
+        game.step(20);
+        assert!(game.get::<Climber>(player).unwrap().climbing);
+        assert!((game.position(player).x - LADDER_X).abs() < 0.5);
+
+        game.tap_key(KeyCode::Space);
+        game.step(20);
+        assert!(!game.get::<Climber>(player).unwrap().climbing);
+        assert!(game.position(player).x > LADDER_X + 10.0);
+    }
+
+    #[test]
+    fn player_stands_on_the_top_of_a_ladder() {
+        let mut game = TestGame::new();
+        let (player, ground_y) = climb_to_the_top(&mut game);
+
+        let height = game.position(player).y - ground_y;
+        assert!(
+            (height - LADDER_HEIGHT).abs() < 1.0,
+            "player is {height} high"
+        );
+        let climber = game.get::<Climber>(player).unwrap();
+        assert!(!climber.climbing && climber.on_ladder_top);
+        assert!(game.get::<GroundDetection>(player).unwrap().on_ground);
+    }
+
+    #[test]
+    fn holding_down_on_the_top_climbs_down_the_ladder() {
+        let mut game = TestGame::new();
+        let (player, ground_y) = climb_to_the_top(&mut game);
+
+        game.press_key(KeyCode::KeyS);
+        game.step(15);
+        assert!(game.get::<Climber>(player).unwrap().climbing);
+        assert!(game.position(player).y - ground_y < LADDER_HEIGHT - 10.0);
+
+        // Reaching the floor
+        game.step(60);
+        game.release_key(KeyCode::KeyS);
+        game.step(5);
+        assert!((game.position(player).y - ground_y).abs() < 1.0);
+    }
+}
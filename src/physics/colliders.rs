@@ -3,6 +3,9 @@ use bevy_ecs_ldtk::prelude::*;
 
 use bevy_rapier2d::prelude::*;
 
+/// Collision group of the item pickups: they rest on the level but never block the player
+pub const PICKUP_GROUP: Group = Group::GROUP_5;
+
 #[derive(Clone, Default, Bundle, LdtkIntCell)]
 pub struct ColliderBundle {
     pub collider: Collider,
@@ -56,6 +59,20 @@ impl From<&EntityInstance> for ColliderBundle {
                 // density: ColliderMassProperties::Density(100.0),
                 ..Default::default()
             },
+            "MovingPlatform" => ColliderBundle {
+                collider: Collider::cuboid(
+                    entity_instance.width as f32 / 2.,
+                    entity_instance.height as f32 / 2.,
+                ),
+                rigid_body: RigidBody::KinematicVelocityBased,
+                // The riders are carried by the platform, not dragged by friction
+                friction: Friction {
+                    coefficient: 0.0,
+                    combine_rule: CoefficientCombineRule::Min,
+                },
+                rotation_constraints,
+                ..Default::default()
+            },
             _ => ColliderBundle::default(),
         }
     }
@@ -73,8 +90,8 @@ impl From<IntGridCell> for SensorBundle {
     fn from(int_grid_cell: IntGridCell) -> SensorBundle {
         let rotation_constraints = LockedAxes::ROTATION_LOCKED;
 
-        // ladder
-        if int_grid_cell.value == 2 {
+        // ladder, spikes and lava
+        if matches!(int_grid_cell.value, 2 | 5 | 6) {
             SensorBundle {
                 collider: Collider::cuboid(8., 8.),
                 sensor: Sensor,
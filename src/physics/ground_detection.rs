@@ -4,15 +4,22 @@ use bevy::prelude::*;
 
 use bevy_rapier2d::prelude::*;
 
+use super::colliders::PICKUP_GROUP;
+use super::one_way_platforms::{OneWayPlatformCollider, PlatformDrop};
+
 #[derive(Component)]
 pub struct GroundSensor {
     pub ground_detection_entity: Entity,
     pub intersecting_ground_entities: HashSet<Entity>,
 }
 
-#[derive(Clone, Default, Component)]
+#[derive(Clone, Default, PartialEq, Component)]
 pub struct GroundDetection {
     pub on_ground: bool,
+    /// Standing only on one-way platforms, that can be dropped through
+    pub on_platform: bool,
+    /// Velocity of the moving body stood on, `None` on static ground
+    pub ground_velocity: Option<Vec2>,
 }
 
 pub fn spawn_ground_sensor(
@@ -36,6 +43,8 @@ pub fn spawn_ground_sensor(
                     .insert(ActiveEvents::COLLISION_EVENTS)
                     .insert(detector_shape)
                     .insert(Sensor)
+                    // Item pickups lying around are not ground to jump from
+                    .insert(CollisionGroups::new(Group::ALL, Group::ALL ^ PICKUP_GROUP))
                     .insert(Transform::from_translation(sensor_translation))
                     .insert(GlobalTransform::default())
                     .insert(GroundSensor {
@@ -81,12 +90,43 @@ pub fn ground_detection(
 }
 
 pub fn update_on_ground(
-    mut ground_detectors: Query<&mut GroundDetection>,
-    ground_sensors: Query<&GroundSensor, Changed<GroundSensor>>,
+    mut ground_detectors: Query<(
+        &mut GroundDetection,
+        Option<&Velocity>,
+        Option<&PlatformDrop>,
+    )>,
+    ground_sensors: Query<&GroundSensor>,
+    platforms: Query<(), With<OneWayPlatformCollider>>,
+    moving_grounds: Query<(&RigidBody, &Velocity)>,
 ) {
     for sensor in &ground_sensors {
-        if let Ok(mut ground_detection) = ground_detectors.get_mut(sensor.ground_detection_entity) {
-            ground_detection.on_ground = !sensor.intersecting_ground_entities.is_empty();
+        if let Ok((mut ground_detection, velocity, drop)) =
+            ground_detectors.get_mut(sensor.ground_detection_entity)
+        {
+            // A platform crossed from below or dropped through is not ground yet
+            let passing_through = velocity.is_some_and(|velocity| velocity.linvel.y > 1.)
+                || drop.is_some_and(PlatformDrop::dropping);
+
+            let (mut solid, mut platform) = (false, false);
+            let mut ground_velocity = None;
+            for &ground in &sensor.intersecting_ground_entities {
+                if !platforms.contains(ground) {
+                    solid = true;
+                } else if !passing_through {
+                    platform = true;
+                }
+                if let Ok((RigidBody::KinematicVelocityBased, velocity)) =
+                    moving_grounds.get(ground)
+                {
+                    ground_velocity = Some(velocity.linvel);
+                }
+            }
+
+            ground_detection.set_if_neq(GroundDetection {
+                on_ground: solid || platform,
+                on_platform: platform && !solid,
+                ground_velocity,
+            });
         }
     }
 }
@@ -1,5 +1,7 @@
+pub mod climbing;
 /// Bundles for auto-loading Rapier colliders as part of the level
 pub mod colliders;
-pub mod climbing;
 pub mod ground_detection;
+pub mod one_way_platforms;
+pub mod wall_detection;
 pub mod walls;
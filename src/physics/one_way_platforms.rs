@@ -0,0 +1,213 @@
+//! One-way platforms.
+//!
+//! The `platform` tiles of the Collisions layer are merged into plates like the walls. Bodies
+//! go up through them and land on top, and the player drops through by holding down while
+//! jumping. The solver contacts are filtered by a Rapier physics hook.
+
+use std::collections::HashSet;
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_rapier2d::rapier::prelude::Vector;
+
+use super::walls::tile_plates;
+
+/// IntGrid value of the one-way platforms in the Collisions layer
+pub const ONE_WAY_PLATFORM_INT_CELL: i32 = 4;
+
+/// How far from vertical a contact can be and still hold a body on a platform (in radians)
+const PLATFORM_CONTACT_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct OneWayPlatform;
+
+/// Marker of the merged colliders spawned for the one-way platforms of a level
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct OneWayPlatformCollider;
+
+#[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
+pub struct OneWayPlatformBundle {
+    one_way_platform: OneWayPlatform,
+}
+
+/// Lets a body fall through the one-way platforms for a while
+#[derive(Clone, Copy, PartialEq, Debug, Default, Component)]
+pub struct PlatformDrop {
+    /// Time left before the platforms hold the body again (in seconds)
+    pub remaining: f32,
+}
+
+impl PlatformDrop {
+    /// Long enough to fall through a platform from a standstill
+    pub const DURATION: f32 = 0.25;
+
+    pub fn dropping(&self) -> bool {
+        self.remaining > 0.
+    }
+}
+
+/// Hook Rapier qui ne garde que les contacts par le dessus des plateformes
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatformCollider>>,
+    drops: Query<'w, 's, &'static PlatformDrop>,
+}
+
+impl BevyPhysicsHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        // The allowed normal points out of the first collider, up from a platform
+        let (allowed_normal, body) = if self.platforms.contains(context.collider1()) {
+            (Vector::y(), context.rigid_body2())
+        } else if self.platforms.contains(context.collider2()) {
+            (-Vector::y(), context.rigid_body1())
+        } else {
+            return;
+        };
+
+        let dropping = body
+            .and_then(|body| self.drops.get(body).ok())
+            .is_some_and(PlatformDrop::dropping);
+        if dropping {
+            context.raw.solver_contacts.clear();
+            return;
+        }
+
+        context
+            .raw
+            .update_as_oneway_platform(&allowed_normal, PLATFORM_CONTACT_ANGLE);
+    }
+}
+
+/// Système qui fusionne les tuiles de plateforme de chaque rangée en un seul collider
+pub fn spawn_one_way_platform_collision(
+    mut commands: Commands,
+    platform_query: Query<(&GridCoords, &Parent), Added<OneWayPlatform>>,
+    layer_query: Query<(&LayerMetadata, &Parent), Without<OneWayPlatform>>,
+) {
+    let mut level_to_platform_locations: HashMap<Entity, (&LayerMetadata, HashSet<GridCoords>)> =
+        HashMap::new();
+
+    platform_query.iter().for_each(|(&grid_coords, parent)| {
+        // As for the walls, the level entity is the grandparent of the tile
+        if let Ok((layer, grandparent)) = layer_query.get(parent.get()) {
+            level_to_platform_locations
+                .entry(grandparent.get())
+                .or_insert_with(|| (layer, HashSet::new()))
+                .1
+                .insert(grid_coords);
+        }
+    });
+
+    for (level_entity, (layer, platforms)) in level_to_platform_locations {
+        let grid_size = layer.grid_size as f32;
+        let plate_stack = tile_plates(&platforms, layer.c_wid, layer.c_hei);
+
+        commands.entity(level_entity).with_children(|level| {
+            // Platforms are never merged across rows, only their top side holds bodies
+            for (y, row) in plate_stack.into_iter().enumerate() {
+                for plate in row {
+                    level.spawn((
+                        Collider::cuboid(
+                            (plate.right - plate.left + 1) as f32 * grid_size / 2.,
+                            grid_size / 2.,
+                        ),
+                        RigidBody::Fixed,
+                        Friction::new(1.0),
+                        ActiveHooks::MODIFY_SOLVER_CONTACTS,
+                        OneWayPlatformCollider,
+                        Transform::from_xyz(
+                            (plate.left + plate.right + 1) as f32 * grid_size / 2.,
+                            (y as f32 + 0.5) * grid_size,
+                            0.,
+                        ),
+                    ));
+                }
+            }
+        });
+    }
+}
+
+/// Système qui décompte le temps de chute à travers les plateformes
+pub fn tick_platform_drops(time: Res<Time>, mut drops: Query<&mut PlatformDrop>) {
+    for mut drop in &mut drops {
+        if drop.dropping() {
+            drop.remaining = (drop.remaining - time.delta_secs()).max(0.);
+        }
+    }
+}
+
+/// Spawns the one-way platforms on the `platform` int cells of the Collisions layer.
+///
+/// The physics hook is given to Rapier with `RapierPhysicsPlugin::<OneWayPlatformHooks>`.
+pub struct OneWayPlatformPlugin;
+
+impl Plugin for OneWayPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_one_way_platform_collision, tick_platform_drops),
+        )
+        .register_ldtk_int_cell_for_layer::<OneWayPlatformBundle>(
+            "Collisions",
+            ONE_WAY_PLATFORM_INT_CELL,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::entities::player::JumpEvent;
+    use crate::physics::ground_detection::GroundDetection;
+    use crate::testing::TestGame;
+
+    /// Middle of the platform of the fixture level, three tiles above the floor
+    const PLATFORM_X: f32 = 224.0;
+    const PLATFORM_HEIGHT: f32 = 48.0;
+
+    /// Jumps onto the platform, returns the player and its height on the floor
+    fn jump_onto_the_platform(game: &mut TestGame) -> (Entity, f32) {
+        game.settle();
+        let player = game.player();
+        game.get_mut::<Transform>(player).translation.x = PLATFORM_X;
+        game.settle();
+        let ground_y = game.position(player).y;
+
+        game.press_key(KeyCode::Space);
+        game.step(20);
+        game.release_key(KeyCode::Space);
+        game.settle();
+        (player, ground_y)
+    }
+
+    #[test]
+    fn player_jumps_up_through_a_platform_and_lands_on_it() {
+        let mut game = TestGame::new();
+        let (player, ground_y) = jump_onto_the_platform(&mut game);
+
+        assert!((game.position(player).y - ground_y - PLATFORM_HEIGHT).abs() < 1.0);
+        let ground = game.get::<GroundDetection>(player).unwrap();
+        assert!(ground.on_ground && ground.on_platform);
+    }
+
+    #[test]
+    fn down_and_jump_drops_through_the_platform() {
+        let mut game = TestGame::new();
+        game.record::<JumpEvent>();
+        let (player, ground_y) = jump_onto_the_platform(&mut game);
+        assert!(game.position(player).y - ground_y > PLATFORM_HEIGHT - 1.0);
+
+        game.press_key(KeyCode::KeyS);
+        game.tap_key(KeyCode::Space);
+        game.release_key(KeyCode::KeyS);
+        game.settle();
+
+        assert!((game.position(player).y - ground_y).abs() < 1.0);
+        let ground = game.get::<GroundDetection>(player).unwrap();
+        assert!(ground.on_ground && !ground.on_platform);
+        // Dropping is not a jump
+        assert_eq!(game.recorded::<JumpEvent>().len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use bevy_rapier2d::prelude::*;
+
+use crate::entities::player::Side;
+
+use super::walls::WallCollider;
+
+#[derive(Component)]
+pub struct WallSensor {
+    pub wall_detection_entity: Entity,
+    pub side: Side,
+    pub intersecting_wall_entities: HashSet<Entity>,
+}
+
+/// Contact with the level walls on each side, for the wall slide and the wall jump
+#[derive(Clone, Default, Component)]
+pub struct WallDetection {
+    pub on_wall_left: bool,
+    pub on_wall_right: bool,
+}
+
+impl WallDetection {
+    /// Side of the wall being touched, the one the entity faces if both are
+    pub fn wall_side(&self, facing: Side) -> Option<Side> {
+        match (self.on_wall_left, self.on_wall_right) {
+            (true, true) => Some(facing),
+            (true, false) => Some(Side::Left),
+            (false, true) => Some(Side::Right),
+            (false, false) => None,
+        }
+    }
+}
+
+pub fn spawn_wall_sensors(
+    mut commands: Commands,
+    detect_walls_for: Query<(Entity, &Collider), Added<WallDetection>>,
+) {
+    for (entity, shape) in &detect_walls_for {
+        if let Some(cuboid) = shape.as_cuboid() {
+            let Vec2 {
+                x: half_extents_x,
+                y: half_extents_y,
+            } = cuboid.half_extents();
+
+            // Shorter than the body so that the floor and the ceiling are not walls
+            let detector_shape = Collider::cuboid(2., half_extents_y / 2.0);
+
+            commands.entity(entity).with_children(|builder| {
+                for (side, direction) in [(Side::Left, -1.), (Side::Right, 1.)] {
+                    builder
+                        .spawn_empty()
+                        .insert(ActiveEvents::COLLISION_EVENTS)
+                        .insert(detector_shape.clone())
+                        .insert(Sensor)
+                        .insert(Transform::from_xyz(direction * half_extents_x, 0., 0.))
+                        .insert(GlobalTransform::default())
+                        .insert(WallSensor {
+                            wall_detection_entity: entity,
+                            side,
+                            intersecting_wall_entities: HashSet::new(),
+                        });
+                }
+            });
+        }
+    }
+}
+
+pub fn wall_detection(
+    mut wall_sensors: Query<&mut WallSensor>,
+    mut collisions: EventReader<CollisionEvent>,
+    walls: Query<Entity, With<WallCollider>>,
+) {
+    for collision_event in collisions.read() {
+        match collision_event {
+            CollisionEvent::Started(e1, e2, _) => {
+                if walls.contains(*e1) {
+                    if let Ok(mut sensor) = wall_sensors.get_mut(*e2) {
+                        sensor.intersecting_wall_entities.insert(*e1);
+                    }
+                } else if walls.contains(*e2) {
+                    if let Ok(mut sensor) = wall_sensors.get_mut(*e1) {
+                        sensor.intersecting_wall_entities.insert(*e2);
+                    }
+                }
+            }
+            CollisionEvent::Stopped(e1, e2, _) => {
+                // The merged walls are despawned with their level, the sensor must forget them
+                if let Ok(mut sensor) = wall_sensors.get_mut(*e2) {
+                    sensor.intersecting_wall_entities.remove(e1);
+                } else if let Ok(mut sensor) = wall_sensors.get_mut(*e1) {
+                    sensor.intersecting_wall_entities.remove(e2);
+                }
+            }
+        }
+    }
+}
+
+pub fn update_on_wall(
+    mut wall_detectors: Query<&mut WallDetection>,
+    wall_sensors: Query<&WallSensor, Changed<WallSensor>>,
+) {
+    for sensor in &wall_sensors {
+        if let Ok(mut wall_detection) = wall_detectors.get_mut(sensor.wall_detection_entity) {
+            let touching = !sensor.intersecting_wall_entities.is_empty();
+            match sensor.side {
+                Side::Left => wall_detection.on_wall_left = touching,
+                Side::Right => wall_detection.on_wall_right = touching,
+            }
+        }
+    }
+}
+
+/// Detects the level walls on the left and on the right of the player.
+pub struct WallDetectionPlugin;
+
+impl Plugin for WallDetectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, spawn_wall_sensors)
+            .add_systems(Update, wall_detection)
+            .add_systems(Update, update_on_wall);
+    }
+}
@@ -0,0 +1,332 @@
+//! Headless simulation harness for the gameplay tests.
+//!
+//! [`TestGame`] builds an `App` without window nor renderer (MinimalPlugins, Rapier and the
+//! game state machine), starts a game session on a small LDtk fixture and steps it frame by frame with a fixed
+//! delta. Scripted keyboard and gamepad input go through the regular Bevy input events, so
+//! the game systems see exactly what they would see with a real device.
+
+use std::time::Duration;
+
+use bevy::app::PluginsState;
+use bevy::ecs::event::EventCursor;
+use bevy::image::{CompressedImageFormats, ImageLoader};
+use bevy::input::gamepad::{
+    GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent,
+    RawGamepadButtonChangedEvent, RawGamepadEvent,
+};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::render::sync_world::SyncWorldPlugin;
+use bevy::render::RenderApp;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::config::GameConfig;
+use crate::core::game_flow::WorldMap;
+use crate::core::save::{apply_pending_checkpoint, PendingCheckpoint, SaveSlots};
+use crate::entities::player::Player;
+use crate::entities::stats::Stats;
+use crate::input::actions::ActionMap;
+use crate::menu::{SettingsMenuState, StickNavigationState};
+use crate::physics::one_way_platforms::OneWayPlatformHooks;
+use crate::{GameState, InGameplay};
+
+/// LDtk fixture used by default: a flat room with a ladder, the player on the left and a
+/// mob on the right (path relative to the `assets` folder)
+pub const HARNESS_FIXTURE: &str = "../tests/fixtures/harness.ldtk";
+
+/// Duration of a simulated frame
+pub const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Maximum number of frames waited for the fixture to be loaded and spawned
+const MAX_LOADING_FRAMES: u32 = 600;
+
+/// Events recorded by [`TestGame::record`], kept across frames
+#[derive(Resource)]
+struct Recorded<E: Event + Clone> {
+    cursor: EventCursor<E>,
+    events: Vec<E>,
+}
+
+fn record_events<E: Event + Clone>(mut recorded: ResMut<Recorded<E>>, events: Res<Events<E>>) {
+    let recorded = &mut *recorded;
+    recorded
+        .events
+        .extend(recorded.cursor.read(&events).cloned());
+}
+
+/// Headless game used by the simulation tests
+pub struct TestGame {
+    pub app: App,
+}
+
+impl TestGame {
+    /// Game loaded from the default fixture, in `GameState::InGame` with the player spawned
+    pub fn new() -> Self {
+        Self::with_fixture(HARNESS_FIXTURE)
+    }
+
+    pub fn with_fixture(path: &'static str) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::input::InputPlugin,
+            StatesPlugin,
+        ))
+        .init_asset::<Image>()
+        .register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE))
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<Shader>()
+        .init_resource::<ClearColor>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+
+        // The tilemap plugin pulled by LdtkPlugin expects a render sub-app while it is built,
+        // but never uses it once the plugins are finished
+        app.insert_sub_app(RenderApp, SubApp::new());
+        app.add_plugins(LdtkPlugin);
+        app.remove_sub_app(RenderApp);
+        // Despawning the tilemaps of a finished session records them for the render world
+        app.add_plugins(SyncWorldPlugin);
+
+        app.add_plugins(RapierPhysicsPlugin::<OneWayPlatformHooks>::pixels_per_meter(100.0))
+            .insert_resource(LdtkSettings {
+                level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
+                    load_level_neighbors: true,
+                },
+                ..default()
+            })
+            // Default bindings: the config file of the developer must not leak in the tests
+            .insert_resource(ActionMap::default())
+            .insert_resource(GameConfig::default())
+            // Empty save slots: the tests never touch the save files
+            .init_resource::<SaveSlots>()
+            .init_resource::<PendingCheckpoint>()
+            // The checkpoints are restored like in SavePlugin, which would read the save files
+            .add_systems(OnEnter(InGameplay), apply_pending_checkpoint)
+            .add_systems(
+                Update,
+                apply_pending_checkpoint.run_if(in_state(GameState::InGame)),
+            )
+            // The session is started once Rapier is set up, like from the main menu
+            .insert_state(GameState::Menu)
+            .add_computed_state::<InGameplay>()
+            .enable_state_scoped_entities::<InGameplay>()
+            .insert_resource(WorldMap(path))
+            .add_plugins(crate::core::game_flow::GameFlowPlugin)
+            .add_plugins(crate::input::InputPlugin)
+            .add_plugins(crate::physics::walls::WallPlugin)
+            .add_plugins(crate::physics::one_way_platforms::OneWayPlatformPlugin)
+            .add_plugins(crate::physics::ground_detection::GroundDetectionPlugin)
+            .add_plugins(crate::physics::wall_detection::WallDetectionPlugin)
+            .add_plugins(crate::physics::climbing::ClimbingPlugin)
+            .add_plugins(crate::entities::player::PlayerPlugin)
+            .add_plugins(crate::entities::enemy::EnemyPlugin)
+            .add_plugins(crate::core::inventory::InventoryPlugin)
+            .add_plugins(crate::core::item_effects::ItemEffectsPlugin)
+            .add_plugins(crate::engine::damage::DamagePlugin)
+            .add_plugins(crate::combat::CombatPlugin)
+            .add_plugins(crate::world::objects::MiscObjectsPlugin)
+            .add_plugins(crate::gui::dialog::DialogPlugin)
+            .add_plugins(crate::core::game_over::GameOverPlugin)
+            .init_state::<SettingsMenuState>()
+            .init_resource::<StickNavigationState>()
+            .add_plugins(crate::gui::pause_menu::PauseMenuPlugin);
+
+        while app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let mut game = Self { app };
+        game.step(1);
+        game.start_session();
+        game
+    }
+
+    /// Enter `GameState::InGame` and wait for the world to be spawned
+    pub fn start_session(&mut self) {
+        self.set_state(GameState::InGame);
+        assert!(
+            self.step_until(MAX_LOADING_FRAMES, |game| game.try_player().is_some()),
+            "the fixture {} should spawn a player",
+            self.app.world().resource::<WorldMap>().0
+        );
+    }
+
+    /// Switch to `state`, the transition happens on the next frame
+    pub fn set_state(&mut self, state: GameState) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+    }
+
+    /// Advance the simulation by `frames` fixed frames
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Step until `condition` holds, at most `max_frames` frames. Returns whether it held.
+    pub fn step_until(&mut self, max_frames: u32, condition: impl Fn(&mut Self) -> bool) -> bool {
+        for _ in 0..max_frames {
+            if condition(self) {
+                return true;
+            }
+            self.step(1);
+        }
+        condition(self)
+    }
+
+    /// Let the player fall and settle on the ground
+    pub fn settle(&mut self) {
+        self.step(60);
+    }
+
+    pub fn press_key(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+    }
+
+    pub fn release_key(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    /// Press a key for one frame
+    pub fn tap_key(&mut self, key_code: KeyCode) {
+        self.press_key(key_code);
+        self.step(1);
+        self.release_key(key_code);
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Connect a virtual gamepad, which becomes the active one on the next frame
+    pub fn connect_gamepad(&mut self) -> Entity {
+        let gamepad = self.app.world_mut().spawn_empty().id();
+        self.app.world_mut().send_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: "Test gamepad".to_string(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        self.step(1);
+        gamepad
+    }
+
+    pub fn press_button(&mut self, gamepad: Entity, button: GamepadButton) {
+        self.send_button(gamepad, button, 1.0);
+    }
+
+    pub fn release_button(&mut self, gamepad: Entity, button: GamepadButton) {
+        self.send_button(gamepad, button, 0.0);
+    }
+
+    /// Press a gamepad button for one frame
+    pub fn tap_button(&mut self, gamepad: Entity, button: GamepadButton) {
+        self.press_button(gamepad, button);
+        self.step(1);
+        self.release_button(gamepad, button);
+    }
+
+    fn send_button(&mut self, gamepad: Entity, button: GamepadButton, value: f32) {
+        self.app.world_mut().send_event(RawGamepadEvent::Button(
+            RawGamepadButtonChangedEvent::new(gamepad, button, value),
+        ));
+    }
+
+    pub fn set_axis(&mut self, gamepad: Entity, axis: GamepadAxis, value: f32) {
+        self.app
+            .world_mut()
+            .send_event(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+    }
+
+    /// Keep every event of type `E` sent from now on, see [`TestGame::recorded`]
+    pub fn record<E: Event + Clone>(&mut self) {
+        let cursor = self.app.world().resource::<Events<E>>().get_cursor();
+        self.app
+            .insert_resource(Recorded::<E> {
+                cursor,
+                events: Vec::new(),
+            })
+            .add_systems(Last, record_events::<E>);
+    }
+
+    pub fn recorded<E: Event + Clone>(&self) -> &[E] {
+        &self
+            .app
+            .world()
+            .get_resource::<Recorded<E>>()
+            .expect("events should be recorded with `TestGame::record`")
+            .events
+    }
+
+    pub fn try_player(&mut self) -> Option<Entity> {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<Player>>()
+            .get_single(self.app.world())
+            .ok()
+    }
+
+    pub fn player(&mut self) -> Entity {
+        self.try_player().expect("the player should be spawned")
+    }
+
+    /// First entity holding the component `C`
+    pub fn find<C: Component>(&mut self) -> Option<Entity> {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<C>>()
+            .iter(self.app.world())
+            .next()
+    }
+
+    pub fn get<C: Component>(&self, entity: Entity) -> Option<&C> {
+        self.app.world().get::<C>(entity)
+    }
+
+    pub fn get_mut<C: Component>(&mut self, entity: Entity) -> Mut<'_, C> {
+        self.app
+            .world_mut()
+            .get_mut::<C>(entity)
+            .expect("the entity should have the component")
+    }
+
+    pub fn stats(&self, entity: Entity) -> Stats {
+        self.get::<Stats>(entity)
+            .cloned()
+            .expect("the entity should have stats")
+    }
+
+    /// World position of an entity
+    pub fn position(&self, entity: Entity) -> Vec2 {
+        self.get::<GlobalTransform>(entity)
+            .expect("the entity should have a transform")
+            .translation()
+            .truncate()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+}
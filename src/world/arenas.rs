@@ -0,0 +1,424 @@
+//! Arena encounters, painted with the `arena` value of the LDtk `Zone` IntGrid layer.
+//!
+//! When the player walks into an arena holding mobs, its exits are sealed with temporary
+//! walls until every mob inside is dead.
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::entities::enemy::Enemy;
+use crate::entities::player::Player;
+use crate::gui::dialog::{HideTopDialog, ShowTopDialog};
+use crate::physics::walls::WALL_INT_CELLS;
+use crate::{GameState, InGameplay};
+
+use super::objects::WorldObjectStates;
+
+/// Durée d'affichage du message de fin d'arène (en secondes)
+const CLEARED_MESSAGE_DURATION: f32 = 3.0;
+
+const SEAL_COLOR: Color = Color::srgba(0.8, 0.2, 0.2, 0.6);
+
+/// Cell of the `Zone` layer marked as `arena`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct ArenaCell;
+
+#[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
+pub struct ArenaCellBundle {
+    arena_cell: ArenaCell,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArenaState {
+    /// Waiting for the player to walk in
+    #[default]
+    Idle,
+    /// Exits sealed until the tracked mobs are dead
+    Active {
+        mobs: Vec<Entity>,
+    },
+    Cleared,
+}
+
+/// Arena made of connected `arena` cells, spawned as a child of its level
+#[derive(Clone, Debug, Component)]
+pub struct Arena {
+    /// Persistent identifier (level iid and first cell) used to remember cleared arenas
+    pub id: String,
+    pub level: Entity,
+    pub grid_size: i32,
+    pub cells: HashSet<IVec2>,
+    /// Open cells around the arena, sealed while it is active
+    pub exits: Vec<IVec2>,
+    pub state: ArenaState,
+}
+
+/// Temporary wall closing an exit of an active arena
+#[derive(Copy, Clone, Debug, Component)]
+pub struct ArenaSeal {
+    pub arena: Entity,
+}
+
+/// Envoyé quand le joueur déclenche une arène
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ArenaStarted {
+    pub arena: Entity,
+}
+
+/// Envoyé quand tous les ennemis d'une arène sont vaincus
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ArenaCleared {
+    pub arena: Entity,
+}
+
+/// Timer hiding the "arena cleared" message
+#[derive(Resource, Default)]
+struct ClearedMessageTimer(Option<Timer>);
+
+/// Cell of the level grid (as `GridCoords`, y going up) holding a position relative to the level
+fn level_cell(relative_position: Vec2, grid_size: i32) -> IVec2 {
+    (relative_position / grid_size as f32).floor().as_ivec2()
+}
+
+/// Système qui regroupe les cases d'arène en arènes connexes, une fois par niveau chargé
+fn build_arenas(
+    mut commands: Commands,
+    cell_query: Query<(&GridCoords, &Parent), Added<ArenaCell>>,
+    parent_query: Query<&Parent, Without<ArenaCell>>,
+    level_query: Query<&LevelIid>,
+    states: Res<WorldObjectStates>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    if cell_query.is_empty() {
+        return;
+    }
+
+    // An IntGrid cell's parent is its layer, the level is its grandparent
+    let mut level_to_cells: HashMap<Entity, HashSet<IVec2>> = HashMap::new();
+    for (grid_coords, parent) in cell_query.iter() {
+        if let Ok(grandparent) = parent_query.get(parent.get()) {
+            level_to_cells
+                .entry(grandparent.get())
+                .or_default()
+                .insert(IVec2::from(*grid_coords));
+        }
+    }
+
+    let ldtk_project = ldtk_project_assets
+        .get(ldtk_projects.single())
+        .expect("Project should be loaded if level has spawned");
+
+    for (level_entity, mut remaining) in level_to_cells {
+        let Ok(level_iid) = level_query.get(level_entity) else {
+            continue;
+        };
+        let level = ldtk_project
+            .as_standalone()
+            .get_loaded_level_by_iid(&level_iid.to_string())
+            .expect("Spawned level should exist in LDtk project");
+
+        let Some(collisions) = level
+            .layer_instances()
+            .iter()
+            .find(|layer| layer.identifier == "Collisions")
+        else {
+            continue;
+        };
+        let is_solid = |cell: IVec2| {
+            // Past the border lies the neighbouring level, which must be sealed off too
+            if cell.x < 0 || cell.y < 0 || cell.x >= collisions.c_wid || cell.y >= collisions.c_hei
+            {
+                return false;
+            }
+            // The IntGrid CSV starts at the top of the level
+            let index = (collisions.c_hei - 1 - cell.y) * collisions.c_wid + cell.x;
+            WALL_INT_CELLS.contains(&collisions.int_grid_csv[index as usize])
+        };
+
+        // Split the cells into connected arenas
+        while let Some(&start) = remaining.iter().next() {
+            let mut cells = HashSet::new();
+            let mut to_visit = vec![start];
+            remaining.remove(&start);
+
+            while let Some(cell) = to_visit.pop() {
+                cells.insert(cell);
+                for neighbour in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y].map(|d| cell + d)
+                {
+                    if remaining.remove(&neighbour) {
+                        to_visit.push(neighbour);
+                    }
+                }
+            }
+
+            let mut exits: Vec<IVec2> = cells
+                .iter()
+                .flat_map(|cell| {
+                    [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y].map(|d| *cell + d)
+                })
+                .filter(|neighbour| !cells.contains(neighbour) && !is_solid(*neighbour))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            exits.sort_by_key(|cell| (cell.x, cell.y));
+
+            let first_cell = cells
+                .iter()
+                .min_by_key(|cell| (cell.x, cell.y))
+                .copied()
+                .unwrap_or_default();
+            let id = format!("{}:{}:{}", level_iid.as_str(), first_cell.x, first_cell.y);
+            let state = if states.cleared_arenas.contains(&id) {
+                ArenaState::Cleared
+            } else {
+                ArenaState::Idle
+            };
+
+            commands.entity(level_entity).with_children(|level| {
+                level.spawn(Arena {
+                    id,
+                    level: level_entity,
+                    grid_size: collisions.grid_size,
+                    cells,
+                    exits,
+                    state,
+                });
+            });
+        }
+    }
+}
+
+/// Système qui ferme l'arène quand le joueur y entre
+fn start_arenas(
+    mut commands: Commands,
+    mut arena_query: Query<(Entity, &mut Arena)>,
+    level_query: Query<&GlobalTransform, With<LevelIid>>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    enemy_query: Query<(Entity, &GlobalTransform), With<Enemy>>,
+    mut states: ResMut<WorldObjectStates>,
+    mut started_events: EventWriter<ArenaStarted>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (arena_entity, mut arena) in arena_query.iter_mut() {
+        if arena.state != ArenaState::Idle {
+            continue;
+        }
+        let Ok(level_transform) = level_query.get(arena.level) else {
+            continue;
+        };
+        let level_origin = level_transform.translation().truncate();
+
+        let player_cell = level_cell(
+            player_transform.translation().truncate() - level_origin,
+            arena.grid_size,
+        );
+        if !arena.cells.contains(&player_cell) {
+            continue;
+        }
+
+        let mobs: Vec<Entity> = enemy_query
+            .iter()
+            .filter(|(_, transform)| {
+                arena.cells.contains(&level_cell(
+                    transform.translation().truncate() - level_origin,
+                    arena.grid_size,
+                ))
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+
+        // Nothing to fight: the arena stays open
+        if mobs.is_empty() {
+            arena.state = ArenaState::Cleared;
+            states.cleared_arenas.insert(arena.id.clone());
+            continue;
+        }
+
+        let grid_size = arena.grid_size as f32;
+        commands.entity(arena.level).with_children(|level| {
+            for exit in &arena.exits {
+                level.spawn((
+                    ArenaSeal {
+                        arena: arena_entity,
+                    },
+                    Sprite::from_color(SEAL_COLOR, Vec2::splat(grid_size)),
+                    Transform::from_translation(((exit.as_vec2() + 0.5) * grid_size).extend(5.)),
+                    Collider::cuboid(grid_size / 2., grid_size / 2.),
+                    RigidBody::Fixed,
+                    Friction::new(1.0),
+                ));
+            }
+        });
+
+        arena.state = ArenaState::Active { mobs };
+        started_events.send(ArenaStarted {
+            arena: arena_entity,
+        });
+    }
+}
+
+/// Système qui rouvre l'arène quand tous ses ennemis sont morts
+fn clear_arenas(
+    mut commands: Commands,
+    mut arena_query: Query<(Entity, &mut Arena)>,
+    seal_query: Query<(Entity, &ArenaSeal)>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut states: ResMut<WorldObjectStates>,
+    mut cleared_events: EventWriter<ArenaCleared>,
+) {
+    for (arena_entity, mut arena) in arena_query.iter_mut() {
+        let ArenaState::Active { mobs } = &arena.state else {
+            continue;
+        };
+
+        // Dead mobs are despawned
+        if mobs.iter().any(|mob| enemy_query.contains(*mob)) {
+            continue;
+        }
+
+        for (seal_entity, seal) in seal_query.iter() {
+            if seal.arena == arena_entity {
+                commands.entity(seal_entity).despawn_recursive();
+            }
+        }
+
+        arena.state = ArenaState::Cleared;
+        states.cleared_arenas.insert(arena.id.clone());
+        cleared_events.send(ArenaCleared {
+            arena: arena_entity,
+        });
+    }
+}
+
+/// Système qui annonce au joueur le début et la fin des arènes
+fn announce_arenas(
+    mut started_events: EventReader<ArenaStarted>,
+    mut cleared_events: EventReader<ArenaCleared>,
+    arena_query: Query<&Arena>,
+    mut dialog_events: EventWriter<ShowTopDialog>,
+    mut message_timer: ResMut<ClearedMessageTimer>,
+) {
+    for event in started_events.read() {
+        if let Ok(arena) = arena_query.get(event.arena) {
+            if let ArenaState::Active { mobs } = &arena.state {
+                info!("Arène {} : {} ennemis", arena.id, mobs.len());
+            }
+        }
+        dialog_events.send(ShowTopDialog {
+            text: "L'arène se referme ! Éliminez tous les ennemis.".to_string(),
+            speaker: None,
+        });
+    }
+
+    for event in cleared_events.read() {
+        if let Ok(arena) = arena_query.get(event.arena) {
+            info!("Arène {} terminée", arena.id);
+        }
+        dialog_events.send(ShowTopDialog {
+            text: "Arène terminée !".to_string(),
+            speaker: None,
+        });
+        message_timer.0 = Some(Timer::from_seconds(
+            CLEARED_MESSAGE_DURATION,
+            TimerMode::Once,
+        ));
+    }
+}
+
+/// Système qui masque le message de fin d'arène après quelques secondes
+fn hide_cleared_message(
+    time: Res<Time>,
+    mut message_timer: ResMut<ClearedMessageTimer>,
+    mut hide_events: EventWriter<HideTopDialog>,
+) {
+    if let Some(timer) = &mut message_timer.0 {
+        if timer.tick(time.delta()).finished() {
+            hide_events.send(HideTopDialog);
+            message_timer.0 = None;
+        }
+    }
+}
+
+fn reset_cleared_message_timer(mut message_timer: ResMut<ClearedMessageTimer>) {
+    message_timer.0 = None;
+}
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ArenaStarted>()
+            .add_event::<ArenaCleared>()
+            .init_resource::<ClearedMessageTimer>()
+            .add_systems(OnExit(InGameplay), reset_cleared_message_timer)
+            .register_ldtk_int_cell_for_layer::<ArenaCellBundle>("Zone", 1)
+            .add_systems(
+                Update,
+                (
+                    build_arenas,
+                    start_arenas,
+                    clear_arenas,
+                    announce_arenas,
+                    hide_cleared_message,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+
+    #[test]
+    fn arena_touching_the_level_border_is_sealed_there() {
+        let mut game = TestGame::new();
+        game.settle();
+        game.record::<ArenaStarted>();
+
+        // The fixture arena runs along the top border, next to the left wall
+        let arena = game.find::<Arena>().unwrap();
+        let exits = game.get::<Arena>(arena).unwrap().exits.clone();
+        for x in 1..=3 {
+            assert!(
+                exits.contains(&IVec2::new(x, 15)),
+                "border above {x} is open"
+            );
+        }
+        assert!(
+            !exits.contains(&IVec2::new(0, 14)),
+            "the wall needs no seal"
+        );
+
+        // Lure the mob and the player into the arena
+        let level_origin = game.position(game.get::<Arena>(arena).unwrap().level);
+        let mob = game.find::<Enemy>().unwrap();
+        let player = game.player();
+        for (entity, x) in [(mob, 56.), (player, 24.)] {
+            let offset = level_origin + Vec2::new(x, 232.) - game.position(entity);
+            game.get_mut::<Transform>(entity).translation += offset.extend(0.);
+        }
+        game.step(2);
+
+        assert_eq!(game.recorded::<ArenaStarted>().len(), 1);
+        let seal_cells: Vec<IVec2> = game
+            .app
+            .world_mut()
+            .query_filtered::<&Transform, With<ArenaSeal>>()
+            .iter(game.app.world())
+            .map(|transform| level_cell(transform.translation.truncate(), 16))
+            .collect();
+        assert_eq!(seal_cells.len(), exits.len());
+        for x in 1..=3 {
+            assert!(seal_cells.contains(&IVec2::new(x, 15)));
+        }
+    }
+}
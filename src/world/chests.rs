@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::core::inventory::{items_from_field, Item};
+use crate::physics::colliders::ColliderBundle;
+
+use super::interaction::{InteractEvent, Interactable};
+use super::objects::WorldObjectStates;
+use super::pickups::{item_icon, item_pickup_bundle, pop_velocities, SpilledFrom};
+
+/// Teinte appliquée au coffre une fois ouvert
+const OPENED_CHEST_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum ChestState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Clone, Debug, Default, Component)]
+pub struct Chest {
+    pub state: ChestState,
+    /// Items spilled when the chest is opened
+    pub content: Vec<Item>,
+}
+
+impl From<&EntityInstance> for Chest {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Chest {
+            state: ChestState::Closed,
+            content: items_from_field(entity_instance, "content"),
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct ChestBundle {
+    #[sprite_sheet]
+    pub sprite_sheet: Sprite,
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[from_entity_instance]
+    pub chest: Chest,
+    pub interactable: Interactable,
+}
+
+/// Spawns `items` popping out of a chest.
+///
+/// The pickups are siblings of the chest so they unload with its level, and remember the
+/// chest so that the ones left behind are spilled again when the level is reloaded.
+fn spill_items(
+    commands: &mut Commands,
+    ldtk_project: &LdtkProject,
+    layer: Entity,
+    chest_iid: &EntityIid,
+    items: &[Item],
+    chest_translation: Vec3,
+) {
+    let spawn_translation = chest_translation + Vec3::new(0., 8., 1.);
+    commands.entity(layer).with_children(|layer| {
+        for (item, linvel) in items.iter().zip(pop_velocities(items.len())) {
+            if let Some(sprite) = item_icon(ldtk_project, *item) {
+                layer.spawn((
+                    item_pickup_bundle(*item, sprite, spawn_translation, linvel),
+                    SpilledFrom(chest_iid.to_string()),
+                ));
+            } else {
+                warn!("No icon found for item {:?}", item);
+            }
+        }
+    });
+}
+
+/// Système qui garde ouverts les coffres déjà ouverts quand leur niveau est rechargé, et
+/// répand à nouveau les objets que le joueur n'a pas ramassés
+fn restore_chest_states(
+    mut commands: Commands,
+    mut chest_query: Query<
+        (&mut Chest, &EntityIid, &Transform, &Parent, &mut Sprite),
+        Added<Chest>,
+    >,
+    states: Res<WorldObjectStates>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let ldtk_project = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle));
+
+    for (mut chest, iid, transform, parent, mut sprite) in chest_query.iter_mut() {
+        if !states.opened_chests.contains(iid.as_str()) {
+            continue;
+        }
+        chest.state = ChestState::Open;
+        sprite.color = OPENED_CHEST_COLOR;
+
+        if let (Some(ldtk_project), Some(items)) =
+            (ldtk_project, states.spilled_items.get(iid.as_str()))
+        {
+            spill_items(
+                &mut commands,
+                ldtk_project,
+                parent.get(),
+                iid,
+                items,
+                transform.translation,
+            );
+        }
+    }
+}
+
+/// Système qui ouvre les coffres et répand leur contenu dans le monde
+fn open_chests(
+    mut commands: Commands,
+    mut interact_events: EventReader<InteractEvent>,
+    mut chest_query: Query<(&mut Chest, &EntityIid, &Transform, &Parent, &mut Sprite)>,
+    mut states: ResMut<WorldObjectStates>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    for event in interact_events.read() {
+        let Ok((mut chest, iid, transform, parent, mut sprite)) = chest_query.get_mut(event.target)
+        else {
+            continue;
+        };
+
+        if chest.state == ChestState::Open {
+            continue;
+        }
+
+        chest.state = ChestState::Open;
+        sprite.color = OPENED_CHEST_COLOR;
+        states.opened_chests.insert(iid.to_string());
+        states
+            .spilled_items
+            .insert(iid.to_string(), chest.content.clone());
+        info!("Coffre ouvert : {:?}", chest.content);
+
+        let Some(ldtk_project) = ldtk_projects
+            .get_single()
+            .ok()
+            .and_then(|handle| ldtk_project_assets.get(handle))
+        else {
+            continue;
+        };
+
+        spill_items(
+            &mut commands,
+            ldtk_project,
+            parent.get(),
+            iid,
+            &chest.content,
+            transform.translation,
+        );
+    }
+}
+
+pub struct ChestPlugin;
+
+impl Plugin for ChestPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<ChestBundle>("Chest")
+            .add_systems(Update, (restore_chest_states, open_chests).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+
+    fn spilled_pickups(game: &mut TestGame) -> Vec<Entity> {
+        game.app
+            .world_mut()
+            .query_filtered::<Entity, With<SpilledFrom>>()
+            .iter(game.app.world())
+            .collect()
+    }
+
+    /// Despawns the fixture level and spawns it again, as when the player comes back to it
+    fn reload_level(game: &mut TestGame) {
+        let level = game.find::<LevelIid>().unwrap();
+        game.app.world_mut().entity_mut(level).insert(Respawn);
+        game.settle();
+    }
+
+    #[test]
+    fn items_left_in_a_chest_are_spilled_again_when_its_level_is_reloaded() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let start = game.get::<Transform>(player).unwrap().translation;
+        let chest = game.find::<Chest>().unwrap();
+        game.app.world_mut().send_event(InteractEvent {
+            player,
+            target: chest,
+        });
+        game.settle();
+        let pickups = spilled_pickups(&mut game);
+        assert_eq!(pickups.len(), 2);
+
+        // Picks one of the two items up
+        let pickup_position = game.position(pickups[0]);
+        game.get_mut::<Transform>(player).translation = pickup_position.extend(0.);
+        game.step(5);
+        assert_eq!(spilled_pickups(&mut game).len(), 1);
+
+        // The player is not part of the level, it must not stand where the items fall
+        game.get_mut::<Transform>(player).translation = start;
+        reload_level(&mut game);
+        let chest = game.find::<Chest>().unwrap();
+        assert_eq!(game.get::<Chest>(chest).unwrap().state, ChestState::Open);
+        assert_eq!(spilled_pickups(&mut game).len(), 1);
+    }
+}
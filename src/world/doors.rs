@@ -0,0 +1,390 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::ldtk::ReferenceToAnEntityInstance;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::entities::enemy::Enemy;
+use crate::entities::stats::Stats;
+use crate::physics::colliders::ColliderBundle;
+use crate::GameState;
+
+use super::interaction::{InteractEvent, Interactable};
+use super::objects::WorldObjectStates;
+
+/// Durée de l'animation d'ouverture d'une porte (en secondes)
+const DOOR_OPENING_DURATION: f32 = 0.5;
+
+const LEVER_ACTIVE_COLOR: Color = Color::srgb(0.3, 0.8, 0.3);
+const LEVER_INACTIVE_COLOR: Color = Color::srgb(0.8, 0.3, 0.3);
+
+/// État d'une porte
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub enum DoorState {
+    /// Fermée mais pas verrouillée, s'ouvre en interagissant
+    #[default]
+    Closed,
+    /// Verrouillée tant que l'entité référencée par `locked_by` n'est pas déclenchée
+    Locked,
+    Opening,
+    Open,
+}
+
+#[derive(Clone, Debug, Default, Component)]
+pub struct Door {
+    pub state: DoorState,
+    /// Whether the door starts locked in the LDtk project
+    pub locked: bool,
+    /// Entity unlocking this door: a lever, another door or a mob to defeat
+    pub locked_by: Option<ReferenceToAnEntityInstance>,
+    pub opening_timer: Timer,
+}
+
+impl From<&EntityInstance> for Door {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        let locked = *entity_instance.get_bool_field("locked").unwrap_or(&false);
+        let locked_by = entity_instance
+            .get_maybe_entity_ref_field("locked_by")
+            .ok()
+            .cloned()
+            .flatten();
+
+        Door {
+            state: if locked {
+                DoorState::Locked
+            } else {
+                DoorState::Closed
+            },
+            locked,
+            locked_by,
+            opening_timer: Timer::from_seconds(DOOR_OPENING_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct DoorBundle {
+    #[sprite_sheet]
+    pub sprite_sheet: Sprite,
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[from_entity_instance]
+    pub door: Door,
+    pub interactable: Interactable,
+
+    // Kept to restore the door collider once it closes again
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Lever {
+    pub is_active: bool,
+}
+
+impl From<&EntityInstance> for Lever {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Lever {
+            is_active: *entity_instance
+                .get_bool_field("is_active")
+                .unwrap_or(&false),
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct LeverBundle {
+    #[from_entity_instance]
+    pub lever: Lever,
+    pub interactable: Interactable,
+}
+
+/// Check whether the entity referenced by a door's `locked_by` field has been triggered.
+///
+/// The referenced entity may live in a level that is not spawned, so the persisted
+/// `WorldObjectStates` are used first, falling back on the LDtk project data.
+fn is_reference_triggered(
+    reference: &ReferenceToAnEntityInstance,
+    states: &WorldObjectStates,
+    ldtk_project: Option<&LdtkProject>,
+) -> bool {
+    let iid = &reference.entity_iid;
+
+    if let Some(is_active) = states.levers.get(iid) {
+        return *is_active;
+    }
+
+    if states.open_doors.contains(iid) || states.defeated_enemies.contains(iid) {
+        return true;
+    }
+
+    // Lever never spawned yet: use its initial value from the LDtk project
+    ldtk_project
+        .and_then(|project| project.get_raw_level_by_iid(&reference.level_iid))
+        .and_then(|level| {
+            level
+                .layer_instances
+                .iter()
+                .flatten()
+                .flat_map(|layer| layer.entity_instances.iter())
+                .find(|entity_instance| entity_instance.iid == *iid)
+        })
+        .filter(|entity_instance| entity_instance.identifier == "Lever")
+        .map(|entity_instance| Lever::from(entity_instance).is_active)
+        .unwrap_or(false)
+}
+
+/// Système qui restaure l'état des leviers quand leur niveau est (re)chargé
+fn restore_lever_states(
+    mut lever_query: Query<(&mut Lever, &EntityIid), Added<Lever>>,
+    mut states: ResMut<WorldObjectStates>,
+) {
+    for (mut lever, iid) in lever_query.iter_mut() {
+        if let Some(is_active) = states.levers.get(iid.as_str()) {
+            lever.is_active = *is_active;
+        } else {
+            states.levers.insert(iid.to_string(), lever.is_active);
+        }
+    }
+}
+
+/// Système qui actionne les leviers avec lesquels le joueur interagit
+fn toggle_levers(
+    mut interact_events: EventReader<InteractEvent>,
+    mut lever_query: Query<(&mut Lever, &EntityIid)>,
+    mut states: ResMut<WorldObjectStates>,
+) {
+    for event in interact_events.read() {
+        if let Ok((mut lever, iid)) = lever_query.get_mut(event.target) {
+            lever.is_active = !lever.is_active;
+            states.levers.insert(iid.to_string(), lever.is_active);
+            info!("Levier {} : {}", iid.as_str(), lever.is_active);
+        }
+    }
+}
+
+/// Système qui met à jour l'apparence des leviers (pas de tuile dans le projet LDtk)
+fn update_lever_visuals(
+    mut commands: Commands,
+    lever_query: Query<(Entity, &Lever), Changed<Lever>>,
+) {
+    for (entity, lever) in lever_query.iter() {
+        let color = if lever.is_active {
+            LEVER_ACTIVE_COLOR
+        } else {
+            LEVER_INACTIVE_COLOR
+        };
+        commands
+            .entity(entity)
+            .insert(Sprite::from_color(color, Vec2::new(4., 14.)));
+    }
+}
+
+/// Système qui restaure l'état des portes quand leur niveau est (re)chargé
+fn restore_door_states(
+    mut commands: Commands,
+    mut door_query: Query<(Entity, &mut Door, &EntityIid, &mut Sprite), Added<Door>>,
+    states: Res<WorldObjectStates>,
+) {
+    for (entity, mut door, iid, mut sprite) in door_query.iter_mut() {
+        if states.open_doors.contains(iid.as_str()) {
+            door.state = DoorState::Open;
+            sprite.color.set_alpha(0.);
+            commands.entity(entity).remove::<Collider>();
+        }
+    }
+}
+
+/// Système qui ouvre les portes non verrouillées avec lesquelles le joueur interagit
+fn open_doors_on_interact(
+    mut interact_events: EventReader<InteractEvent>,
+    mut door_query: Query<&mut Door>,
+) {
+    for event in interact_events.read() {
+        if let Ok(mut door) = door_query.get_mut(event.target) {
+            match door.state {
+                DoorState::Closed => {
+                    door.state = DoorState::Opening;
+                    door.opening_timer.reset();
+                }
+                DoorState::Locked => info!("La porte est verrouillée"),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Machine à états des portes : verrouillée -> ouverture -> ouverte (et inversement)
+fn update_doors(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut door_query: Query<(Entity, &mut Door, &EntityIid, &EntityInstance, &mut Sprite)>,
+    mut states: ResMut<WorldObjectStates>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let ldtk_project = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle));
+
+    for (entity, mut door, iid, entity_instance, mut sprite) in door_query.iter_mut() {
+        let unlocked = !door.locked
+            || door
+                .locked_by
+                .as_ref()
+                .is_some_and(|reference| is_reference_triggered(reference, &states, ldtk_project));
+
+        match door.state {
+            DoorState::Locked => {
+                if unlocked {
+                    door.state = DoorState::Opening;
+                    door.opening_timer.reset();
+                }
+            }
+            DoorState::Closed => {}
+            DoorState::Opening => {
+                if !unlocked {
+                    door.state = DoorState::Locked;
+                    sprite.color.set_alpha(1.);
+                    continue;
+                }
+
+                door.opening_timer.tick(time.delta());
+                sprite.color.set_alpha(1. - door.opening_timer.fraction());
+
+                if door.opening_timer.finished() {
+                    door.state = DoorState::Open;
+                    states.open_doors.insert(iid.to_string());
+                    commands.entity(entity).remove::<Collider>();
+                }
+            }
+            DoorState::Open => {
+                // The lever has been switched back: lock the door again
+                if !unlocked {
+                    door.state = DoorState::Locked;
+                    states.open_doors.remove(iid.as_str());
+                    sprite.color.set_alpha(1.);
+                    commands
+                        .entity(entity)
+                        .insert(ColliderBundle::from(entity_instance).collider);
+                }
+            }
+        }
+    }
+}
+
+/// Observer qui garde la trace des ennemis vaincus (ils peuvent verrouiller des portes)
+fn record_defeated_enemies(
+    trigger: Trigger<OnRemove, Enemy>,
+    enemy_query: Query<(&EntityIid, &Stats)>,
+    mut states: ResMut<WorldObjectStates>,
+) {
+    // Enemies are also removed when their level unloads, only keep the dead ones
+    if let Ok((iid, stats)) = enemy_query.get(trigger.entity()) {
+        if stats.life <= 0 {
+            states.defeated_enemies.insert(iid.to_string());
+        }
+    }
+}
+
+pub struct DoorPlugin;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<DoorBundle>("Door")
+            .register_ldtk_entity::<LeverBundle>("Lever")
+            .add_observer(record_defeated_enemies)
+            .add_systems(
+                Update,
+                (
+                    restore_lever_states,
+                    toggle_levers,
+                    update_lever_visuals,
+                    restore_door_states,
+                    open_doors_on_interact,
+                    // The doors stop opening while the game is paused
+                    update_doors.run_if(in_state(GameState::InGame)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+
+    fn door_state(game: &mut TestGame) -> (Entity, DoorState) {
+        let door = game.find::<Door>().unwrap();
+        (door, game.get::<Door>(door).unwrap().state)
+    }
+
+    /// Walks the player to the lever of the fixture level and pulls it
+    fn pull_the_lever(game: &mut TestGame) {
+        let player = game.player();
+        let lever = game.find::<Lever>().unwrap();
+        let lever_x = game.position(lever).x;
+        game.get_mut::<Transform>(player).translation.x = lever_x + 8.;
+        game.step(5);
+        game.tap_key(KeyCode::KeyO);
+        game.step(1);
+    }
+
+    #[test]
+    fn lever_opens_the_door_it_unlocks() {
+        let mut game = TestGame::new();
+        game.settle();
+        let (door, state) = door_state(&mut game);
+        assert_eq!(state, DoorState::Locked);
+        assert!(game.get::<Collider>(door).is_some());
+
+        pull_the_lever(&mut game);
+        assert!(game.step_until(60, |game| door_state(game).1 == DoorState::Open));
+        assert!(game.get::<Collider>(door).is_none());
+
+        // Switching the lever back locks the door again
+        pull_the_lever(&mut game);
+        assert_eq!(door_state(&mut game).1, DoorState::Locked);
+        game.step(1);
+        assert!(game.get::<Collider>(door).is_some());
+    }
+
+    #[test]
+    fn locked_door_stays_shut_without_its_trigger() {
+        let mut game = TestGame::new();
+        game.settle();
+        let player = game.player();
+        let (door, _) = door_state(&mut game);
+
+        game.app.world_mut().send_event(InteractEvent {
+            player,
+            target: door,
+        });
+        game.settle();
+
+        assert_eq!(door_state(&mut game).1, DoorState::Locked);
+        assert!(game.get::<Collider>(door).is_some());
+        let iid = game.get::<EntityIid>(door).unwrap().to_string();
+        let states = game.app.world().resource::<WorldObjectStates>();
+        assert!(!states.open_doors.contains(&iid));
+    }
+
+    #[test]
+    fn door_state_survives_a_level_reload() {
+        let mut game = TestGame::new();
+        game.settle();
+        pull_the_lever(&mut game);
+        assert!(game.step_until(60, |game| door_state(game).1 == DoorState::Open));
+
+        let level = game.find::<LevelIid>().unwrap();
+        game.app.world_mut().entity_mut(level).insert(Respawn);
+        game.settle();
+
+        let (door, state) = door_state(&mut game);
+        assert_eq!(state, DoorState::Open);
+        assert!(game.get::<Collider>(door).is_none());
+        let lever = game.find::<Lever>().unwrap();
+        assert!(game.get::<Lever>(lever).unwrap().is_active);
+    }
+}
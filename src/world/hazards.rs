@@ -0,0 +1,180 @@
+//! Hazard tiles of the `Collisions` IntGrid layer (spikes and lava).
+//!
+//! Touching a hazard, or falling out of the level, hurts the player through the usual
+//! [`DamageEvent`] and brings it back to the last ground it stood on.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::game_flow::{update_level_selection, FellOutOfLevelEvent};
+use crate::engine::damage::DamageEvent;
+use crate::entities::player::Player;
+use crate::physics::colliders::SensorBundle;
+use crate::physics::ground_detection::GroundDetection;
+use crate::physics::one_way_platforms::PlatformDrop;
+use crate::GameState;
+
+/// IntGrid values of the hazards in the Collisions layer
+pub const SPIKES_INT_CELL: i32 = 5;
+pub const LAVA_INT_CELL: i32 = 6;
+
+/// Damage taken when falling out of the level
+const FALL_DAMAGE: i32 = 1;
+
+/// Tile hurting the player on contact
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Hazard {
+    pub damage: i32,
+}
+
+impl From<IntGridCell> for Hazard {
+    fn from(int_grid_cell: IntGridCell) -> Hazard {
+        match int_grid_cell.value {
+            LAVA_INT_CELL => Hazard { damage: 2 },
+            _ => Hazard { damage: 1 },
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkIntCell)]
+pub struct HazardBundle {
+    #[from_int_grid_cell]
+    pub sensor_bundle: SensorBundle,
+    #[from_int_grid_cell]
+    pub hazard: Hazard,
+}
+
+/// Last position where the player stood on the ground, where hazards send it back
+#[derive(Clone, Copy, PartialEq, Debug, Default, Component)]
+pub struct SafeGround(pub Option<Vec3>);
+
+/// Système qui blesse le joueur touchant un danger ou tombé hors du niveau, puis le ramène
+/// sur le dernier sol sûr
+fn hurt_player_on_hazards(
+    mut collisions: EventReader<CollisionEvent>,
+    mut fell_events: EventReader<FellOutOfLevelEvent>,
+    hazards: Query<&Hazard>,
+    mut players: Query<(&mut Transform, &mut Velocity, &SafeGround), With<Player>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let mut hits: Vec<(Entity, Entity, i32)> = Vec::new();
+
+    for collision in collisions.read() {
+        if let CollisionEvent::Started(e1, e2, _) = collision {
+            if let (true, Ok(hazard)) = (players.contains(*e1), hazards.get(*e2)) {
+                hits.push((*e1, *e2, hazard.damage));
+            } else if let (true, Ok(hazard)) = (players.contains(*e2), hazards.get(*e1)) {
+                hits.push((*e2, *e1, hazard.damage));
+            }
+        }
+    }
+    for event in fell_events.read() {
+        hits.push((event.entity, event.level, FALL_DAMAGE));
+    }
+
+    for (player, source, damage) in hits {
+        let Ok((mut transform, mut velocity, safe_ground)) = players.get_mut(player) else {
+            continue;
+        };
+        damage_events.send(DamageEvent::new(player, source, damage));
+
+        if let Some(position) = safe_ground.0 {
+            transform.translation = position;
+            velocity.linvel = Vec2::ZERO;
+        }
+    }
+}
+
+/// Système qui retient la dernière position du joueur sur le sol
+fn record_safe_ground(
+    mut players: Query<
+        (
+            &Transform,
+            &GroundDetection,
+            Option<&PlatformDrop>,
+            &mut SafeGround,
+        ),
+        With<Player>,
+    >,
+) {
+    for (transform, ground_detection, drop, mut safe_ground) in &mut players {
+        let dropping = drop.is_some_and(PlatformDrop::dropping);
+        // A moving platform may be gone when the player comes back
+        let safe =
+            ground_detection.on_ground && ground_detection.ground_velocity.is_none() && !dropping;
+        // Until it lands, the player comes back where it appeared
+        if safe || safe_ground.0.is_none() {
+            safe_ground.0 = Some(transform.translation);
+        }
+    }
+}
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (hurt_player_on_hazards, record_safe_ground)
+                .chain()
+                // The kill-plane is checked first, a player out of the level is never safe
+                .after(update_level_selection)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .register_ldtk_int_cell_for_layer::<HazardBundle>("Collisions", SPIKES_INT_CELL)
+        .register_ldtk_int_cell_for_layer::<HazardBundle>("Collisions", LAVA_INT_CELL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::damage::Invincibility;
+    use crate::testing::TestGame;
+
+    /// On the floor of the fixture level, between the mob and the spikes
+    const SAFE_X: f32 = 416.0;
+    /// Left side of the spikes lying on the floor
+    const SPIKES_LEFT: f32 = 432.0;
+
+    fn stand_on_safe_ground(game: &mut TestGame) -> Entity {
+        game.settle();
+        let player = game.player();
+        game.get_mut::<Transform>(player).translation.x = SAFE_X;
+        game.settle();
+        player
+    }
+
+    #[test]
+    fn spikes_hurt_the_player_and_send_it_back_to_safe_ground() {
+        let mut game = TestGame::new();
+        let player = stand_on_safe_ground(&mut game);
+        let life = game.stats(player).life;
+
+        game.press_key(KeyCode::KeyD);
+        assert!(game.step_until(60, |game| game.get::<Invincibility>(player).is_some()));
+        game.release_key(KeyCode::KeyD);
+        game.settle();
+
+        assert_eq!(game.stats(player).life, life - 1);
+        assert!(game.position(player).x < SPIKES_LEFT);
+        assert!(game.get::<GroundDetection>(player).unwrap().on_ground);
+    }
+
+    #[test]
+    fn falling_out_of_the_level_sends_the_player_back() {
+        let mut game = TestGame::new();
+        let player = stand_on_safe_ground(&mut game);
+        let safe_position = game.position(player);
+        let life = game.stats(player).life;
+
+        game.get_mut::<Transform>(player).translation.y -= 300.0;
+        game.step(3);
+
+        assert_eq!(game.stats(player).life, life - FALL_DAMAGE);
+        assert!(game.position(player).distance(safe_position) < 2.0);
+        game.settle();
+        assert!(game.get::<GroundDetection>(player).unwrap().on_ground);
+    }
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+/// Distance (in pixels) under which the player can interact with an object
+pub const INTERACTION_RANGE: f32 = 24.0;
+
+/// Marker for world objects the player can interact with (save points, levers, chests...)
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Interactable;
+
+/// Event sent when the player uses the interact action next to an interactable object
+#[derive(Event)]
+pub struct InteractEvent {
+    pub player: Entity,
+    pub target: Entity,
+}
+
+/// Find the closest interactable entity within `INTERACTION_RANGE` of `position`
+pub fn find_interaction_target(
+    position: Vec2,
+    interactables: &Query<(Entity, &GlobalTransform), With<Interactable>>,
+) -> Option<Entity> {
+    interactables
+        .iter()
+        .map(|(entity, transform)| {
+            (
+                entity,
+                transform.translation().truncate().distance(position),
+            )
+        })
+        .filter(|(_, distance)| *distance <= INTERACTION_RANGE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<InteractEvent>();
+    }
+}
@@ -1,2 +1,16 @@
-/// World objects (chests, doors, pumpkins, etc.)
+/// Arena encounters sealing their exits until their mobs are defeated
+pub mod arenas;
+/// Chests spilling their content when opened
+pub mod chests;
+/// Doors and the levers opening them
+pub mod doors;
+/// Spikes and lava, and the respawn at the last safe ground
+pub mod hazards;
+/// Player interactions with world objects
+pub mod interaction;
+/// Platforms moving along a path and carrying what stands on them
+pub mod moving_platforms;
+/// Misc world objects (pumpkins, save points) and the persisted object states
 pub mod objects;
+/// Items lying in the world
+pub mod pickups;
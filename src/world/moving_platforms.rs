@@ -0,0 +1,376 @@
+//! Platforms moving along a path, placed as `MovingPlatform` entities in LDtk.
+//!
+//! The path uses the same `patrol` points field as the mobs. The platform goes back and
+//! forth along it (or loops when `looping` is set), waiting `wait` seconds at each point.
+//! Bodies standing on it are carried, and a jump from it keeps its velocity.
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::entities::enemy::Patrol;
+use crate::physics::colliders::ColliderBundle;
+use crate::GameState;
+
+const PLATFORM_COLOR: Color = Color::srgb(0.55, 0.4, 0.3);
+
+/// Platform following the points of its path
+#[derive(Clone, PartialEq, Debug, Default, Component)]
+pub struct MovingPlatform {
+    pub path: Patrol,
+    /// Goes back to the first point after the last one instead of turning back
+    pub looping: bool,
+    /// Speed along the path (in pixels per second)
+    pub speed: f32,
+    /// Pause at each point (in seconds)
+    pub wait: f32,
+    /// Time left before leaving the current point
+    pub wait_timer: f32,
+    /// Position when the riders were last carried
+    pub last_position: Option<Vec2>,
+}
+
+impl MovingPlatform {
+    pub const DEFAULT_SPEED: f32 = 60.;
+    pub const DEFAULT_WAIT: f32 = 0.5;
+
+    /// Selects the next point of the path
+    pub fn advance(&mut self) {
+        let path = &mut self.path;
+        let last = path.points.len() - 1;
+
+        if self.looping {
+            path.index = (path.index + 1) % path.points.len();
+            return;
+        }
+
+        if path.index == last {
+            path.forward = false;
+        } else if path.index == 0 {
+            path.forward = true;
+        }
+        if path.forward {
+            path.index += 1;
+        } else {
+            path.index -= 1;
+        }
+    }
+}
+
+impl LdtkEntity for MovingPlatform {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        tileset: Option<&Handle<Image>>,
+        tileset_definition: Option<&TilesetDefinition>,
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlasLayout>,
+    ) -> MovingPlatform {
+        MovingPlatform {
+            path: Patrol::bundle_entity(
+                entity_instance,
+                layer_instance,
+                tileset,
+                tileset_definition,
+                asset_server,
+                texture_atlases,
+            ),
+            looping: *entity_instance.get_bool_field("looping").unwrap_or(&false),
+            speed: *entity_instance
+                .get_float_field("speed")
+                .unwrap_or(&Self::DEFAULT_SPEED),
+            wait: *entity_instance
+                .get_float_field("wait")
+                .unwrap_or(&Self::DEFAULT_WAIT),
+            wait_timer: 0.,
+            last_position: None,
+        }
+    }
+}
+
+fn platform_sprite(entity_instance: &EntityInstance) -> Sprite {
+    Sprite {
+        color: PLATFORM_COLOR,
+        custom_size: Some(Vec2::new(
+            entity_instance.width as f32,
+            entity_instance.height as f32,
+        )),
+        ..default()
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct MovingPlatformBundle {
+    #[ldtk_entity]
+    pub moving_platform: MovingPlatform,
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[with(platform_sprite)]
+    pub sprite: Sprite,
+}
+
+/// Sensor lying on top of a moving platform, tracking the bodies standing on it
+#[derive(Component)]
+pub struct PlatformRiders {
+    pub platform: Entity,
+    pub riders: HashSet<Entity>,
+}
+
+pub fn spawn_rider_sensors(
+    mut commands: Commands,
+    platforms: Query<(Entity, &Collider), Added<MovingPlatform>>,
+) {
+    for (entity, shape) in &platforms {
+        if let Some(cuboid) = shape.as_cuboid() {
+            let Vec2 {
+                x: half_extents_x,
+                y: half_extents_y,
+            } = cuboid.half_extents();
+
+            commands.entity(entity).with_children(|builder| {
+                builder.spawn((
+                    Collider::cuboid(half_extents_x, 2.),
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    Transform::from_xyz(0., half_extents_y + 2., 0.),
+                    PlatformRiders {
+                        platform: entity,
+                        riders: HashSet::new(),
+                    },
+                ));
+            });
+        }
+    }
+}
+
+pub fn detect_riders(
+    mut sensors: Query<&mut PlatformRiders>,
+    mut collisions: EventReader<CollisionEvent>,
+) {
+    for collision in collisions.read() {
+        match collision {
+            CollisionEvent::Started(e1, e2, _) => {
+                if let Ok(mut sensor) = sensors.get_mut(*e1) {
+                    sensor.riders.insert(*e2);
+                } else if let Ok(mut sensor) = sensors.get_mut(*e2) {
+                    sensor.riders.insert(*e1);
+                }
+            }
+            CollisionEvent::Stopped(e1, e2, _) => {
+                if let Ok(mut sensor) = sensors.get_mut(*e1) {
+                    sensor.riders.remove(e2);
+                } else if let Ok(mut sensor) = sensors.get_mut(*e2) {
+                    sensor.riders.remove(e1);
+                }
+            }
+        }
+    }
+}
+
+/// Système qui déplace les plateformes vers le prochain point de leur chemin
+pub fn move_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(&mut MovingPlatform, &Transform, &mut Velocity)>,
+) {
+    let delta = time.delta_secs();
+    if delta == 0. {
+        return;
+    }
+
+    for (mut platform, transform, mut velocity) in &mut platforms {
+        velocity.linvel = Vec2::ZERO;
+        if platform.path.points.len() <= 1 {
+            continue;
+        }
+        if platform.wait_timer > 0. {
+            platform.wait_timer -= delta;
+            continue;
+        }
+
+        let to_target =
+            platform.path.points[platform.path.index] - transform.translation.truncate();
+        let distance = to_target.length();
+        if distance < 0.5 {
+            platform.advance();
+            platform.wait_timer = platform.wait;
+            continue;
+        }
+
+        // Slows down on the last frame so that the point is reached exactly
+        let step = (platform.speed * delta).min(distance);
+        velocity.linvel = to_target / distance * step / delta;
+    }
+}
+
+/// Système qui emmène les corps posés sur une plateforme avec elle
+///
+/// The riders follow the distance the physics actually moved the platform since the last
+/// frame: a frame may last longer than the physics step, so its velocity would overshoot.
+pub fn carry_riders(
+    sensors: Query<&PlatformRiders>,
+    mut platforms: Query<(Entity, &mut MovingPlatform, &Transform)>,
+    mut riders: Query<(&RigidBody, &mut Transform), Without<MovingPlatform>>,
+) {
+    let mut displacements = HashMap::new();
+    for (entity, mut platform, transform) in &mut platforms {
+        let position = transform.translation.truncate();
+        if let Some(last_position) = platform.last_position.replace(position) {
+            displacements.insert(entity, position - last_position);
+        }
+    }
+
+    for sensor in &sensors {
+        let Some(&displacement) = displacements.get(&sensor.platform) else {
+            continue;
+        };
+        if displacement == Vec2::ZERO {
+            continue;
+        }
+
+        for &rider in &sensor.riders {
+            if let Ok((RigidBody::Dynamic, mut transform)) = riders.get_mut(rider) {
+                transform.translation += displacement.extend(0.);
+            }
+        }
+    }
+}
+
+pub struct MovingPlatformPlugin;
+
+impl Plugin for MovingPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (spawn_rider_sensors, detect_riders))
+            .add_systems(
+                Update,
+                (move_platforms, carry_riders)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .register_ldtk_entity::<MovingPlatformBundle>("MovingPlatform");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::physics::ground_detection::GroundDetection;
+    use crate::testing::{TestGame, FRAME};
+
+    fn platform_on(points: usize, looping: bool) -> MovingPlatform {
+        MovingPlatform {
+            path: Patrol {
+                points: vec![Vec2::ZERO; points],
+                index: 1,
+                forward: true,
+            },
+            looping,
+            ..default()
+        }
+    }
+
+    fn visited(platform: &mut MovingPlatform, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                platform.advance();
+                platform.path.index
+            })
+            .collect()
+    }
+
+    #[test]
+    fn path_is_followed_back_and_forth_or_in_a_loop() {
+        assert_eq!(visited(&mut platform_on(3, false), 5), vec![2, 1, 0, 1, 2]);
+        assert_eq!(visited(&mut platform_on(3, true), 5), vec![2, 0, 1, 2, 0]);
+    }
+
+    /// Puts the player on the platform of the fixture level, waits for it to move right
+    fn ride_the_platform(game: &mut TestGame) -> (Entity, Entity) {
+        game.settle();
+        let player = game.player();
+        let platform = game.find::<MovingPlatform>().unwrap();
+
+        let on_top = game.position(platform) + Vec2::new(0., 20.);
+        game.get_mut::<Transform>(player).translation = on_top.extend(0.);
+        assert!(game.step_until(120, |game| {
+            game.get::<GroundDetection>(player).unwrap().on_ground
+                && game.get::<Velocity>(platform).unwrap().linvel.x > 0.
+        }));
+        (player, platform)
+    }
+
+    #[test]
+    fn platform_goes_to_the_end_of_its_path_and_back() {
+        let mut game = TestGame::new();
+        game.settle();
+        let platform = game.find::<MovingPlatform>().unwrap();
+
+        // The path of the fixture platform is 56 pixels long
+        let (mut left, mut right) = (f32::INFINITY, f32::NEG_INFINITY);
+        for _ in 0..240 {
+            game.step(1);
+            let x = game.position(platform).x;
+            (left, right) = (left.min(x), right.max(x));
+        }
+        assert!(
+            (right - left - 56.).abs() < 1.,
+            "moved between {left} and {right}"
+        );
+
+        // Waits at the ends of the path
+        assert!(game.step_until(120, |game| (game.position(platform).x - right).abs() < 0.5));
+        game.step(2);
+        assert_eq!(game.get::<Velocity>(platform).unwrap().linvel, Vec2::ZERO);
+    }
+
+    #[test]
+    fn player_standing_on_a_platform_moves_with_it() {
+        let mut game = TestGame::new();
+        let (player, platform) = ride_the_platform(&mut game);
+        let player_start = game.position(player).x;
+        let platform_start = game.position(platform).x;
+
+        game.step(20);
+
+        let platform_moved = game.position(platform).x - platform_start;
+        assert!(platform_moved > 10.);
+        assert!((game.position(player).x - player_start - platform_moved).abs() < 2.);
+    }
+
+    #[test]
+    fn riders_follow_the_platform_when_frames_last_longer_than_a_physics_step() {
+        let mut game = TestGame::new();
+        let (player, platform) = ride_the_platform(&mut game);
+        // The physics only advances by one step of 1/60 s per frame
+        game.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME * 2));
+        game.step(1);
+        let player_start = game.position(player).x;
+        let platform_start = game.position(platform).x;
+
+        game.step(10);
+
+        let platform_moved = game.position(platform).x - platform_start;
+        assert!(platform_moved > 5.);
+        assert!((game.position(player).x - player_start - platform_moved).abs() < 2.);
+    }
+
+    #[test]
+    fn jumping_from_a_moving_platform_keeps_its_speed() {
+        let mut game = TestGame::new();
+        let (player, platform) = ride_the_platform(&mut game);
+        let platform_speed = game.get::<Velocity>(platform).unwrap().linvel.x;
+
+        game.tap_key(KeyCode::Space);
+        game.step(10);
+
+        assert!(!game.get::<GroundDetection>(player).unwrap().on_ground);
+        let speed = game.get::<Velocity>(player).unwrap().linvel.x;
+        assert!(
+            (speed - platform_speed).abs() < 1.,
+            "player moves at {speed}"
+        );
+    }
+}
@@ -1,36 +1,73 @@
 use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
 use bevy_ecs_ldtk::prelude::*;
 
-use crate::physics::colliders::ColliderBundle;
+use crate::core::inventory::Item;
+use crate::InGameplay;
+
+use super::arenas::ArenaPlugin;
+use super::chests::ChestPlugin;
+use super::doors::DoorPlugin;
+use super::hazards::HazardPlugin;
+use super::interaction::{Interactable, InteractionPlugin};
+use super::moving_platforms::MovingPlatformPlugin;
+use super::pickups::PickupPlugin;
+
+/// State of the world objects, keyed by LDtk entity iid.
+///
+/// Levels are despawned when they are not a neighbour of the current level anymore,
+/// so anything the player changed in the world is kept here to be restored on respawn.
+#[derive(Resource, Debug, Default)]
+pub struct WorldObjectStates {
+    pub levers: HashMap<String, bool>,
+    pub open_doors: HashSet<String>,
+    pub defeated_enemies: HashSet<String>,
+    pub opened_chests: HashSet<String>,
+    /// Items spilled by the opened chests that the player has not picked up yet
+    pub spilled_items: HashMap<String, Vec<Item>>,
+    pub collected_pickups: HashSet<String>,
+    pub cleared_arenas: HashSet<String>,
+}
 
 #[derive(Clone, Default, Bundle, LdtkEntity)]
-pub struct ChestBundle {
-    #[sprite_sheet]
+pub struct PumpkinsBundle {
+    #[sprite_sheet(no_grid)]
     pub sprite_sheet: Sprite,
-    #[from_entity_instance]
-    pub collider_bundle: ColliderBundle,
 }
 
+/// Marker for the save points placed in the LDtk map
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct SavePoint;
+
 #[derive(Clone, Default, Bundle, LdtkEntity)]
-pub struct DoorBundle {
+pub struct SaveBundle {
     #[sprite_sheet]
     pub sprite_sheet: Sprite,
-    #[from_entity_instance]
-    pub collider_bundle: ColliderBundle,
+    pub save_point: SavePoint,
+    pub interactable: Interactable,
 }
 
-#[derive(Clone, Default, Bundle, LdtkEntity)]
-pub struct PumpkinsBundle {
-    #[sprite_sheet(no_grid)]
-    pub sprite_sheet: Sprite,
+/// The world of a new session is the one of the LDtk project (or of the loaded checkpoint)
+fn reset_world_object_states(mut states: ResMut<WorldObjectStates>) {
+    *states = WorldObjectStates::default();
 }
 
 pub struct MiscObjectsPlugin;
 
 impl Plugin for MiscObjectsPlugin {
     fn build(&self, app: &mut App) {
-        app.register_ldtk_entity::<ChestBundle>("Chest")
-            .register_ldtk_entity::<DoorBundle>("Door")
-            .register_ldtk_entity::<PumpkinsBundle>("Pumpkins");
+        app.add_plugins((
+            InteractionPlugin,
+            DoorPlugin,
+            ChestPlugin,
+            PickupPlugin,
+            ArenaPlugin,
+            HazardPlugin,
+            MovingPlatformPlugin,
+        ))
+        .init_resource::<WorldObjectStates>()
+        .add_systems(OnExit(InGameplay), reset_world_object_states)
+        .register_ldtk_entity::<PumpkinsBundle>("Pumpkins")
+        .register_ldtk_entity::<SaveBundle>("Save");
     }
 }
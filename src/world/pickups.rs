@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::inventory::{Item, ItemPickedUp};
+use crate::entities::player::Player;
+use crate::physics::colliders::PICKUP_GROUP;
+use crate::GameState;
+
+use super::objects::WorldObjectStates;
+
+/// Identifier of the LDtk enum listing the items
+const ITEM_ENUM_IDENTIFIER: &str = "Item";
+
+/// Displayed size of an item icon in the world (the icons are 32x32 in the atlas)
+const PICKUP_SIZE: f32 = 12.;
+
+/// Item lying in the world, waiting to be picked up
+#[derive(Clone, Copy, Debug, Component)]
+pub struct ItemPickup {
+    pub item: Item,
+}
+
+impl From<&EntityInstance> for ItemPickup {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        let name = entity_instance
+            .get_enum_field("item")
+            .expect("item field should be correctly typed");
+
+        ItemPickup {
+            item: Item::from_ldtk_name(name)
+                .unwrap_or_else(|| panic!("unknown item {} in the LDtk project", name)),
+        }
+    }
+}
+
+/// Sensor of the pickups placed in the LDtk map: they float in place until collected
+#[derive(Clone, Default, Bundle)]
+pub struct PickupSensorBundle {
+    pub collider: Collider,
+    pub sensor: Sensor,
+    pub active_events: ActiveEvents,
+    pub collision_groups: CollisionGroups,
+}
+
+fn pickup_sensor(_: &EntityInstance) -> PickupSensorBundle {
+    PickupSensorBundle {
+        collider: Collider::cuboid(PICKUP_SIZE / 2., PICKUP_SIZE / 2.),
+        sensor: Sensor,
+        active_events: ActiveEvents::COLLISION_EVENTS,
+        collision_groups: CollisionGroups::new(PICKUP_GROUP, Group::ALL),
+    }
+}
+
+#[derive(Clone, Bundle, LdtkEntity)]
+pub struct PickupBundle {
+    #[from_entity_instance]
+    pub pickup: ItemPickup,
+    #[with(pickup_sensor)]
+    pub sensor: PickupSensorBundle,
+}
+
+/// Pickup spilled by an opened chest, identified by the iid of the chest
+#[derive(Clone, Debug, Component)]
+pub struct SpilledFrom(pub String);
+
+/// Delay before a dropped item can be collected, so it is not picked up again right away
+#[derive(Component)]
+pub struct PickupDelay(pub Timer);
+
+/// Image and rect of an item icon, as set in the LDtk `Item` enum definition
+pub fn item_icon_image(ldtk_project: &LdtkProject, item: Item) -> Option<(Handle<Image>, Rect)> {
+    let tile_rect = ldtk_project
+        .json_data()
+        .defs
+        .enums
+        .iter()
+        .find(|enum_definition| enum_definition.identifier == ITEM_ENUM_IDENTIFIER)?
+        .values
+        .iter()
+        .find(|value| value.id == item.ldtk_name())?
+        .tile_rect?;
+
+    let image = ldtk_project
+        .tileset_map()
+        .get(&tile_rect.tileset_uid)?
+        .clone();
+
+    Some((
+        image,
+        Rect::new(
+            tile_rect.x as f32,
+            tile_rect.y as f32,
+            (tile_rect.x + tile_rect.w) as f32,
+            (tile_rect.y + tile_rect.h) as f32,
+        ),
+    ))
+}
+
+/// Build the sprite of an item lying in the world
+pub fn item_icon(ldtk_project: &LdtkProject, item: Item) -> Option<Sprite> {
+    let (image, rect) = item_icon_image(ldtk_project, item)?;
+
+    Some(Sprite {
+        image,
+        rect: Some(rect),
+        custom_size: Some(Vec2::splat(PICKUP_SIZE)),
+        ..default()
+    })
+}
+
+/// Components of an item pickup thrown at `translation` with the given initial velocity.
+///
+/// Pickups are dynamic bodies so they fall on the ground, but they are in `PICKUP_GROUP`
+/// so they never block the player.
+pub fn item_pickup_bundle(
+    item: Item,
+    sprite: Sprite,
+    translation: Vec3,
+    linvel: Vec2,
+) -> impl Bundle {
+    (
+        ItemPickup { item },
+        sprite,
+        Transform::from_translation(translation),
+        RigidBody::Dynamic,
+        Collider::cuboid(PICKUP_SIZE / 2., PICKUP_SIZE / 2.),
+        LockedAxes::ROTATION_LOCKED,
+        Friction::new(1.0),
+        Velocity::linear(linvel),
+        ActiveEvents::COLLISION_EVENTS,
+        CollisionGroups::new(PICKUP_GROUP, Group::ALL),
+    )
+}
+
+/// Initial velocities spreading `count` items in a small fan above their source
+pub fn pop_velocities(count: usize) -> impl Iterator<Item = Vec2> {
+    let center = (count as f32 - 1.) / 2.;
+    (0..count).map(move |index| Vec2::new((index as f32 - center) * 60., 250.))
+}
+
+/// Système qui donne leur icône aux objets placés dans la carte LDtk
+fn setup_pickup_sprites(
+    mut commands: Commands,
+    pickup_query: Query<(Entity, &ItemPickup), Without<Sprite>>,
+    ldtk_projects: Query<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let Some(ldtk_project) = ldtk_projects
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle))
+    else {
+        return;
+    };
+
+    for (entity, pickup) in pickup_query.iter() {
+        match item_icon(ldtk_project, pickup.item) {
+            Some(sprite) => {
+                commands.entity(entity).insert(sprite);
+            }
+            None => {
+                warn!("No icon found for item {:?}", pickup.item);
+                commands.entity(entity).insert(Sprite::default());
+            }
+        }
+    }
+}
+
+/// Système qui retire les objets de la carte déjà ramassés quand leur niveau est rechargé
+fn remove_collected_pickups(
+    mut commands: Commands,
+    pickup_query: Query<(Entity, &EntityIid), Added<ItemPickup>>,
+    states: Res<WorldObjectStates>,
+) {
+    for (entity, iid) in pickup_query.iter() {
+        if states.collected_pickups.contains(iid.as_str()) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Système qui ramasse les objets touchés par le joueur
+#[allow(clippy::too_many_arguments)]
+fn collect_pickups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut picked_up_events: EventWriter<ItemPickedUp>,
+    player_query: Query<Entity, With<Player>>,
+    pickup_query: Query<
+        (&ItemPickup, Option<&EntityIid>, Option<&SpilledFrom>),
+        Without<PickupDelay>,
+    >,
+    mut states: ResMut<WorldObjectStates>,
+    mut expired_delays: RemovedComponents<PickupDelay>,
+    rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
+) {
+    let mut collected = HashSet::new();
+    let mut touches: Vec<(Entity, Entity)> = Vec::new();
+
+    for collision_event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = collision_event else {
+            continue;
+        };
+
+        if player_query.contains(*entity1) {
+            touches.push((*entity1, *entity2));
+        } else if player_query.contains(*entity2) {
+            touches.push((*entity2, *entity1));
+        }
+    }
+
+    // A dropped item still touching the player when its delay ends sends no new collision
+    // event, the contact is looked up instead
+    if let Ok(context) = rapier_context.get_single() {
+        for pickup_entity in expired_delays.read() {
+            for player in &player_query {
+                let touching = context
+                    .contact_pair(player, pickup_entity)
+                    .is_some_and(|pair| pair.has_any_active_contact())
+                    || context.intersection_pair(player, pickup_entity) == Some(true);
+                if touching {
+                    touches.push((player, pickup_entity));
+                }
+            }
+        }
+    }
+
+    for (player, pickup_entity) in touches {
+        let Ok((pickup, iid, spilled_from)) = pickup_query.get(pickup_entity) else {
+            continue;
+        };
+        // The player may touch the same pickup with several colliders in one frame
+        if !collected.insert(pickup_entity) {
+            continue;
+        }
+        commands.entity(pickup_entity).despawn_recursive();
+
+        // Only the pickups placed in the LDtk map respawn with their level
+        if let Some(iid) = iid {
+            states.collected_pickups.insert(iid.to_string());
+        }
+        // The items left by a chest are spilled again when its level is reloaded
+        if let Some(SpilledFrom(chest)) = spilled_from {
+            if let Some(items) = states.spilled_items.get_mut(chest) {
+                if let Some(index) = items.iter().position(|item| *item == pickup.item) {
+                    items.remove(index);
+                }
+            }
+        }
+
+        picked_up_events.send(ItemPickedUp {
+            entity: player,
+            item: pickup.item,
+            count: 1,
+        });
+    }
+}
+
+/// Système qui rend ramassables les objets jetés une fois leur délai écoulé
+fn tick_pickup_delays(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut delay_query: Query<(Entity, &mut PickupDelay)>,
+) {
+    for (entity, mut delay) in delay_query.iter_mut() {
+        if delay.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<PickupDelay>();
+        }
+    }
+}
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<PickupBundle>("Pickup")
+            .add_systems(
+                Update,
+                (
+                    remove_collected_pickups,
+                    setup_pickup_sprites,
+                    tick_pickup_delays.run_if(in_state(GameState::InGame)),
+                    collect_pickups,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+
+    #[test]
+    fn dropped_item_under_the_player_is_picked_up_once_its_delay_ends() {
+        let mut game = TestGame::new();
+        game.record::<ItemPickedUp>();
+        game.settle();
+        let player = game.player();
+
+        let translation = game.get::<Transform>(player).unwrap().translation;
+        game.app.world_mut().spawn((
+            item_pickup_bundle(Item::Meat, Sprite::default(), translation, Vec2::ZERO),
+            PickupDelay(Timer::from_seconds(0.5, TimerMode::Once)),
+        ));
+        game.step(10);
+        assert!(game.recorded::<ItemPickedUp>().is_empty());
+
+        // The player has not moved: the item is picked up without a new collision
+        game.settle();
+        let picked_up = game.recorded::<ItemPickedUp>();
+        assert_eq!(picked_up.len(), 1);
+        assert_eq!(picked_up[0].item, Item::Meat);
+    }
+}